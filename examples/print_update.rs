@@ -1,9 +1,9 @@
 use anyhow::Result;
-use telegram_bot_api_rs::bot::Bot;
+use telegram_bot_api_rs::{bot::Bot, token::Token};
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
-    let token = std::env::var("TOKEN").expect("TOKEN is not Set");
+    let token = Token::from_env("TOKEN")?;
     let bot = Bot::new(token);
     bot.start_get_updates(Default::default());
     let mut subscriber = bot.subscribe_updates();
@@ -1,16 +1,18 @@
 use anyhow::Result;
-use telegram_bot_api_rs::{available_methods::payload::SendMessagePayload, bot::Bot};
+use telegram_bot_api_rs::{available_methods::payload::SendMessagePayload, bot::Bot, token::Token};
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
-    let token = std::env::var("TOKEN").expect("TOKEN is not Set");
+    let token = Token::from_env("TOKEN")?;
+    let chat_id = std::env::var("CHAT_ID")
+        .expect("CHAT_ID is not Set")
+        .parse()?;
     let bot = Bot::new(token);
     bot.send_message(&SendMessagePayload {
-        chat_id: todo!(),
-        text: todo!(),
+        chat_id,
+        text: "Hello from telegram_bot_api_rs".to_string(),
         ..Default::default()
     })
-    .await
-    .unwrap();
+    .await?;
     Ok(())
 }
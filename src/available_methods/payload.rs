@@ -1,16 +1,14 @@
-use std::path::Path;
-
-use reqwest::multipart::Form;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     available_types::{
-        BotCommand, BotCommandScope, ChatAdministratorRights, ChatPermissions, ForceReply,
-        InlineKeyboardMarkup, InputMedia, InputPaidMedia, InputPollOption, LinkPreviewOptions,
-        MenuButton, MessageEntity, ReactionType, ReplyKeyboardMarkup, ReplyKeyboardRemove,
-        ReplyParameters,
+        BotCommand, BotCommandScope, ChatAdministratorRights, ChatId, ChatPermissions, ForceReply,
+        InlineKeyboardMarkup, InputFile, InputMedia, InputPaidMedia, InputPollOption,
+        LinkPreviewOptions, MenuButton, MessageEntity, ReactionType, ReplyKeyboardMarkup,
+        ReplyKeyboardRemove, ReplyParameters,
     },
-    utils::{file_to_multipart, ToMultipart},
+    transport::Form,
+    utils::ToMultipart,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,7 +24,7 @@ pub enum ReplyMarkup {
 pub struct SendMessagePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub text: String,
@@ -50,10 +48,10 @@ pub struct SendMessagePayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ForwardMessagePayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
-    pub from_chat_id: String,
+    pub from_chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -63,10 +61,10 @@ pub struct ForwardMessagePayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ForwardMessagesPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
-    pub from_chat_id: String,
+    pub from_chat_id: ChatId,
     pub message_ids: Vec<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_notification: Option<bool>,
@@ -76,10 +74,10 @@ pub struct ForwardMessagesPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CopyMessagePayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
-    pub from_chat_id: String,
+    pub from_chat_id: ChatId,
     pub message_id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption: Option<String>,
@@ -101,10 +99,10 @@ pub struct CopyMessagePayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CopyMessagesPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
-    pub from_chat_id: String,
+    pub from_chat_id: ChatId,
     pub message_ids: Vec<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_notification: Option<bool>,
@@ -118,10 +116,10 @@ pub struct CopyMessagesPayload {
 pub struct SendPhotoPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
-    pub photo: String,
+    pub photo: InputFile,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -158,14 +156,9 @@ impl ToMultipart for SendPhotoPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
-            let path = Path::new(&self.photo);
-            if path.is_file() {
-                form = file_to_multipart("photo".to_string(), path, form).await?;
-            } else {
-                form = form.text("photo", self.photo);
-            }
-            form = form.text("chat_id", self.chat_id);
+            let mut form = Form::new();
+            form = self.photo.add_to_form("photo", form).await?;
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(caption) = self.caption {
                 form = form.text("caption", caption);
             }
@@ -209,10 +202,10 @@ impl ToMultipart for SendPhotoPayload {
 pub struct SendAudioPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
-    pub audio: String,
+    pub audio: InputFile,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -226,7 +219,7 @@ pub struct SendAudioPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub thumbnail: Option<String>,
+    pub thumbnail: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -253,14 +246,9 @@ impl ToMultipart for SendAudioPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
-            let path = Path::new(&self.audio);
-            if path.is_file() {
-                form = file_to_multipart("audio".to_string(), path, form).await?;
-            } else {
-                form = form.text("audio", self.audio);
-            }
-            form = form.text("chat_id", self.chat_id);
+            let mut form = Form::new();
+            form = self.audio.add_to_form("audio", form).await?;
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(caption) = self.caption {
                 form = form.text("caption", caption);
             }
@@ -280,12 +268,7 @@ impl ToMultipart for SendAudioPayload {
                 form = form.text("title", title);
             }
             if let Some(thumbnail) = self.thumbnail {
-                let path = Path::new(&thumbnail);
-                if path.is_file() {
-                    form = file_to_multipart("thumbnail".to_string(), path, form).await?;
-                } else {
-                    form = form.text("thumbnail", thumbnail);
-                }
+                form = thumbnail.add_to_form("thumbnail", form).await?;
             }
             if let Some(disable_notification) = &self.disable_notification {
                 form = form.text("disable_notification", disable_notification.to_string());
@@ -311,13 +294,13 @@ impl ToMultipart for SendAudioPayload {
 pub struct SendDocumentPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub document: Option<String>,
+    pub document: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub thumbnail: Option<String>,
+    pub thumbnail: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -352,24 +335,14 @@ impl ToMultipart for SendDocumentPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             if let Some(document) = self.document {
-                let path = Path::new(&document);
-                if path.is_file() {
-                    form = file_to_multipart("document".to_string(), path, form).await?;
-                } else {
-                    form = form.text("document", document);
-                }
+                form = document.add_to_form("document", form).await?;
             }
             if let Some(thumbnail) = self.thumbnail {
-                let path = Path::new(&thumbnail);
-                if path.is_file() {
-                    form = file_to_multipart("thumbnail".to_string(), path, form).await?;
-                } else {
-                    form = form.text("thumbnail", thumbnail);
-                }
-            }
-            form = form.text("chat_id", self.chat_id);
+                form = thumbnail.add_to_form("thumbnail", form).await?;
+            }
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(caption) = self.caption {
                 form = form.text("caption", caption);
             }
@@ -409,11 +382,11 @@ impl ToMultipart for SendDocumentPayload {
 pub struct SendVideoPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub video: Option<String>,
+    pub video: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -421,7 +394,7 @@ pub struct SendVideoPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub thumbnail: Option<String>,
+    pub thumbnail: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -460,24 +433,14 @@ impl ToMultipart for SendVideoPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             if let Some(video) = self.video {
-                let path = Path::new(&video);
-                if path.is_file() {
-                    form = file_to_multipart("video".to_string(), path, form).await?;
-                } else {
-                    form = form.text("video", video);
-                }
+                form = video.add_to_form("video", form).await?;
             }
             if let Some(thumbnail) = self.thumbnail {
-                let path = Path::new(&thumbnail);
-                if path.is_file() {
-                    form = file_to_multipart("thumbnail".to_string(), path, form).await?;
-                } else {
-                    form = form.text("thumbnail", thumbnail);
-                }
-            }
-            form = form.text("chat_id", self.chat_id);
+                form = thumbnail.add_to_form("thumbnail", form).await?;
+            }
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(caption) = self.caption {
                 form = form.text("caption", caption);
             }
@@ -523,11 +486,11 @@ impl ToMultipart for SendVideoPayload {
 pub struct SendAnimationPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub animation: Option<String>,
+    pub animation: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -535,7 +498,7 @@ pub struct SendAnimationPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub thumbnail: Option<String>,
+    pub thumbnail: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -572,24 +535,14 @@ impl ToMultipart for SendAnimationPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             if let Some(animation) = self.animation {
-                let path = Path::new(&animation);
-                if path.is_file() {
-                    form = file_to_multipart("animation".to_string(), path, form).await?;
-                } else {
-                    form = form.text("animation", animation);
-                }
+                form = animation.add_to_form("animation", form).await?;
             }
             if let Some(thumbnail) = self.thumbnail {
-                let path = Path::new(&thumbnail);
-                if path.is_file() {
-                    form = file_to_multipart("thumbnail".to_string(), path, form).await?;
-                } else {
-                    form = form.text("thumbnail", thumbnail);
-                }
-            }
-            form = form.text("chat_id", self.chat_id);
+                form = thumbnail.add_to_form("thumbnail", form).await?;
+            }
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(caption) = self.caption {
                 form = form.text("caption", caption);
             }
@@ -632,11 +585,11 @@ impl ToMultipart for SendAnimationPayload {
 pub struct SendVoicePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub voice: Option<String>,
+    pub voice: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub caption: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -671,16 +624,11 @@ impl ToMultipart for SendVoicePayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             if let Some(voice) = self.voice {
-                let path = Path::new(&voice);
-                if path.is_file() {
-                    form = file_to_multipart("voice".to_string(), path, form).await?;
-                } else {
-                    form = form.text("voice", voice);
-                }
-            }
-            form = form.text("chat_id", self.chat_id);
+                form = voice.add_to_form("voice", form).await?;
+            }
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(caption) = self.caption {
                 form = form.text("caption", caption);
             }
@@ -717,17 +665,17 @@ impl ToMultipart for SendVoicePayload {
 pub struct SendVideoNotePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub video_note: Option<String>,
+    pub video_note: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub length: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub thumbnail: Option<String>,
+    pub thumbnail: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_notification: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -754,24 +702,14 @@ impl ToMultipart for SendVideoNotePayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             if let Some(video_note) = self.video_note {
-                let path = Path::new(&video_note);
-                if path.is_file() {
-                    form = file_to_multipart("video_note".to_string(), path, form).await?;
-                } else {
-                    form = form.text("video_note", video_note);
-                }
+                form = video_note.add_to_form("video_note", form).await?;
             }
             if let Some(thumbnail) = self.thumbnail {
-                let path = Path::new(&thumbnail);
-                if path.is_file() {
-                    form = file_to_multipart("thumbnail".to_string(), path, form).await?;
-                } else {
-                    form = form.text("thumbnail", thumbnail);
-                }
-            }
-            form = form.text("chat_id", self.chat_id);
+                form = thumbnail.add_to_form("thumbnail", form).await?;
+            }
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(duration) = &self.duration {
                 form = form.text("duration", duration.to_string());
             }
@@ -802,7 +740,7 @@ impl ToMultipart for SendVideoNotePayload {
 pub struct SendPaidMediaPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub star_count: i64,
     pub media: Vec<InputPaidMedia>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -837,13 +775,13 @@ impl ToMultipart for SendPaidMediaPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             for media in self.media.iter_mut() {
                 form = media.try_part(form).await?;
             }
 
             form = form.text("media", serde_json::to_string(&self.media)?);
-            form = form.text("chat_id", self.chat_id);
+            form = form.text("chat_id", self.chat_id.to_string());
             form = form.text("star_count", self.star_count.to_string());
             if let Some(caption) = &self.caption {
                 form = form.text("caption", caption.to_string());
@@ -881,7 +819,7 @@ impl ToMultipart for SendPaidMediaPayload {
 pub struct SendMediaGroupPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub media: Vec<InputMedia>,
@@ -909,12 +847,12 @@ impl ToMultipart for SendMediaGroupPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             for media in self.media.iter_mut() {
                 form = media.try_part(form).await?;
             }
             form = form.text("media", serde_json::to_string(&self.media)?);
-            form = form.text("chat_id", self.chat_id);
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(message_thread_id) = self.message_thread_id {
                 form = form.text("message_thread_id", message_thread_id.to_string());
             }
@@ -942,7 +880,7 @@ impl ToMultipart for SendMediaGroupPayload {
 pub struct SendLocationPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub latitude: f64,
@@ -971,7 +909,7 @@ pub struct SendLocationPayload {
 pub struct SendVenuePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub latitude: f64,
@@ -1002,7 +940,7 @@ pub struct SendVenuePayload {
 pub struct SendContactPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub phone_number: String,
@@ -1027,7 +965,7 @@ pub struct SendContactPayload {
 pub struct SendPollPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub question: String,
@@ -1072,7 +1010,7 @@ pub struct SendPollPayload {
 pub struct SendDicePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1093,7 +1031,7 @@ pub struct SendDicePayload {
 pub struct SendChatActionPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub action: String,
@@ -1101,7 +1039,7 @@ pub struct SendChatActionPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetMessageReactionPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub message_id: i64,
     pub reaction: Vec<ReactionType>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1124,7 +1062,7 @@ pub struct GetFilePayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BanChatMemberPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub until_date: Option<i64>,
@@ -1134,7 +1072,7 @@ pub struct BanChatMemberPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UnbanChatMemberPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub only_if_banned: Option<bool>,
@@ -1142,7 +1080,7 @@ pub struct UnbanChatMemberPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RestrictChatMemberPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
     pub permissions: ChatPermissions,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1153,7 +1091,7 @@ pub struct RestrictChatMemberPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct PromoteChatMemberPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_anonymous: Option<bool>,
@@ -1189,26 +1127,26 @@ pub struct PromoteChatMemberPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetChatAdministratorCustomTitlePayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
     pub custom_title: String,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BanChatSenderPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub sender_chat_id: i64,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UnbanChatSenderPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub sender_chat_id: i64,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetChatPermissionsPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub permissions: ChatPermissions,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub use_independent_chat_permissions: Option<bool>,
@@ -1216,12 +1154,12 @@ pub struct SetChatPermissionsPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ExportChatInviteLinkPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CreateChatInviteLinkPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1234,7 +1172,7 @@ pub struct CreateChatInviteLinkPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EditChatInviteLinkPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub invite_link: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -1248,7 +1186,7 @@ pub struct EditChatInviteLinkPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CreateChatSubscriptionInviteLinkPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     pub subscription_period: i64,
@@ -1257,7 +1195,7 @@ pub struct CreateChatSubscriptionInviteLinkPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EditChatSubscriptionInviteLinkPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub invite_link: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -1265,26 +1203,26 @@ pub struct EditChatSubscriptionInviteLinkPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RevokeChatInviteLinkPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub invite_link: String,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ApproveChatJoinRequestPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct DeclineChatJoinRequestPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetChatPhotoPayload {
-    pub chat_id: String,
-    pub photo: String,
+    pub chat_id: ChatId,
+    pub photo: InputFile,
 }
 
 impl ToMultipart for SetChatPhotoPayload {
@@ -1301,13 +1239,9 @@ impl ToMultipart for SetChatPhotoPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
-            let path = Path::new(&self.photo);
-            if path.is_file() {
-                form = file_to_multipart("photo".to_string(), path, form).await?;
-            } else {
-                form = form.text("photo", self.photo);
-            }
+            let mut form = Form::new();
+            form = self.photo.add_to_form("photo", form).await?;
+            form = form.text("chat_id", self.chat_id.to_string());
             Ok(form)
         })
     }
@@ -1315,18 +1249,18 @@ impl ToMultipart for SetChatPhotoPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct DeleteChatPhotoPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetChatTitlePayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub title: String,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetChatDescriptionPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
@@ -1335,7 +1269,7 @@ pub struct SetChatDescriptionPayload {
 pub struct PinChatMessagePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub message_id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_notification: Option<bool>,
@@ -1345,36 +1279,72 @@ pub struct PinChatMessagePayload {
 pub struct UnpinChatMessagePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ChatIdPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
+}
+
+/// Gives every method sharing a payload type its own type, so each can implement
+/// [`TelegramMethod`](crate::telegram_method::TelegramMethod) with its own name.
+macro_rules! payload_newtypes {
+    ($($name:ident($inner:ty),)*) => {$(
+        #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub $inner);
+
+        impl From<$inner> for $name {
+            fn from(payload: $inner) -> Self {
+                Self(payload)
+            }
+        }
+    )*};
+}
+
+payload_newtypes! {
+    UnpinAllChatMessagesPayload(UnpinChatMessagePayload),
+    LeaveChatPayload(ChatIdPayload),
+    GetChatPayload(ChatIdPayload),
+    GetChatAdministratorsPayload(ChatIdPayload),
+    GetChatMemberCountPayload(ChatIdPayload),
+    CloseForumTopicPayload(ForumTopicPayload),
+    ReopenForumTopicPayload(ForumTopicPayload),
+    DeleteForumTopicPayload(ForumTopicPayload),
+    UnpinAllForumTopicMessagesPayload(ForumTopicPayload),
+    CloseGeneralForumTopicPayload(ChatIdPayload),
+    ReopenGeneralForumTopicPayload(ChatIdPayload),
+    HideGeneralForumTopicPayload(ChatIdPayload),
+    UnhideGeneralForumTopicPayload(ChatIdPayload),
+    UnpinAllGeneralForumTopicMessagesPayload(ChatIdPayload),
+    GetMyNamePayload(LanguageCodePayload),
+    GetMyDescriptionPayload(LanguageCodePayload),
+    GetMyShortDescriptionPayload(LanguageCodePayload),
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct GetChatMemberPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetChatStickerSetPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub sticker_set_name: String,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct DeleteChatStickerSetPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CreateForumTopicPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub icon_color: Option<i64>,
@@ -1384,7 +1354,7 @@ pub struct CreateForumTopicPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EditForumTopicPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub message_thread_id: i64,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1393,13 +1363,13 @@ pub struct EditForumTopicPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ForumTopicPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub message_thread_id: i64,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EditGeneralForumTopicPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub name: String,
 }
 
@@ -1418,7 +1388,7 @@ pub struct AnswerCallbackQueryPayload {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct GetUserChatBoostsPayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub user_id: i64,
 }
 
@@ -1465,6 +1435,13 @@ pub struct LanguageCodePayload {
     pub language_code: Option<String>,
 }
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GetChatMenuButtonPayload {
+    /// The default menu button is returned when omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chat_id: Option<ChatId>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetMyDescriptionPayload {
     pub description: String,
@@ -1482,7 +1459,7 @@ pub struct SetMyShortDescriptionPayload {
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetChatMenuButtonPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<i64>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub menu_button: Option<MenuButton>,
 }
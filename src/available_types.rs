@@ -1,11 +1,12 @@
 #![allow(non_snake_case)]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::stickers::types::Sticker;
-use crate::utils::file_to_multipart;
-use anyhow::Result;
+use crate::transport::{FileData, FilePart, FileReader, Form, Part};
+use anyhow::{Context, Result};
 use rand::Rng as _;
 use serde::{de, Deserialize, Deserializer, Serialize};
+use tokio::io::AsyncRead;
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct User {
@@ -33,6 +34,99 @@ pub struct User {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub has_main_web_app: Option<bool>,
 }
+/// Unique identifier of a chat, or the `@username` of a channel or supergroup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum ChatId {
+    Id(i64),
+    /// Including the leading `@`.
+    Username(String),
+}
+
+impl Default for ChatId {
+    fn default() -> Self {
+        ChatId::Id(0)
+    }
+}
+
+impl ChatId {
+    /// Accepts the username with or without the leading `@`.
+    pub fn username(username: &str) -> Result<Self> {
+        let name = username.strip_prefix('@').unwrap_or(username);
+        if !(5..=32).contains(&name.len())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            anyhow::bail!("Invalid chat username: {}", username);
+        }
+        Ok(ChatId::Username(format!("@{}", name)))
+    }
+}
+
+impl std::fmt::Display for ChatId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChatId::Id(id) => write!(f, "{}", id),
+            ChatId::Username(username) => f.write_str(username),
+        }
+    }
+}
+
+impl std::str::FromStr for ChatId {
+    type Err = anyhow::Error;
+
+    /// Parses a numeric id or an `@username`.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.parse::<i64>() {
+            Ok(id) => Ok(ChatId::Id(id)),
+            Err(_) if s.starts_with('@') => ChatId::username(s),
+            Err(_) => anyhow::bail!("Invalid chat id: {}", s),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ChatId {
+    fn deserialize<D>(deserializer: D) -> Result<ChatId, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Id(i64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Id(id) => Ok(ChatId::Id(id)),
+            Raw::Text(text) => text.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+impl From<i64> for ChatId {
+    fn from(id: i64) -> Self {
+        ChatId::Id(id)
+    }
+}
+
+impl From<&Chat> for ChatId {
+    fn from(chat: &Chat) -> Self {
+        ChatId::Id(chat.id)
+    }
+}
+
+impl From<&Message> for ChatId {
+    fn from(message: &Message) -> Self {
+        ChatId::Id(message.chat.id)
+    }
+}
+
+impl From<&User> for ChatId {
+    /// The private chat with the user.
+    fn from(user: &User) -> Self {
+        ChatId::Id(user.id)
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Chat {
     pub id: i64,
@@ -82,12 +176,14 @@ pub struct File {
 }
 
 impl File {
-    pub fn get_file_path(&self, token: &str) -> String {
-        format!(
-            "https://api.telegram.org/file/bot{}/{}",
-            token,
-            self.file_path.as_ref().unwrap()
-        )
+    /// Download url of the file, `None` if `getFile` returned no `file_path`.
+    #[deprecated(
+        note = "use `Bot::download_file` or `Bot::file_location`, which honour the configured api url"
+    )]
+    pub fn get_file_path(&self, token: &str) -> Option<String> {
+        self.file_path
+            .as_ref()
+            .map(|path| format!("https://api.telegram.org/file/bot{}/{}", token, path))
     }
 }
 
@@ -516,7 +612,7 @@ pub struct ReplyParameters {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<String>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allow_sending_without_reply: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -582,7 +678,7 @@ impl<'de> Deserialize<'de> for MaybeInaccessibleMessage {
         let value = serde_json::Value::deserialize(deserializer)?;
         if value
             .get("date")
-            .map_or(false, |v| v.as_i64().unwrap_or(0) != 0)
+            .is_some_and(|v| v.as_i64().unwrap_or(0) != 0)
         {
             Message::deserialize(value)
                 .map(|m: Message| MaybeInaccessibleMessage::Message(Box::new(m)))
@@ -1474,11 +1570,11 @@ pub enum BotCommandScope {
     #[serde(rename = "all_chat_administrators")]
     AllChatAdministrators,
     #[serde(rename = "chat")]
-    Chat { chat_id: String },
+    Chat { chat_id: ChatId },
     #[serde(rename = "chat_administrators")]
-    ChatAdministrators { chat_id: String },
+    ChatAdministrators { chat_id: ChatId },
     #[serde(rename = "chat_member")]
-    ChatMember { chat_id: String, user_id: i64 },
+    ChatMember { chat_id: ChatId, user_id: i64 },
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -1576,12 +1672,249 @@ pub struct ResponseParameters {
     pub retry_after: Option<i64>,
 }
 
+/// A file to send: one already stored by Telegram, a url, or content to upload.
+#[derive(Clone)]
+pub enum InputFile {
+    FileId(String),
+    Url(String),
+    Path(PathBuf),
+    Memory {
+        data: Vec<u8>,
+        file_name: String,
+        mime: Option<String>,
+    },
+    /// Streamed once when the request is sent; a retried request fails.
+    Reader {
+        reader: FileReader,
+        file_name: String,
+        mime: Option<String>,
+        len: Option<u64>,
+    },
+    /// `attach://<name>` reference to a file uploaded in the same request.
+    Attach(String),
+}
+
+impl Default for InputFile {
+    fn default() -> Self {
+        InputFile::FileId(String::new())
+    }
+}
+
+impl std::fmt::Debug for InputFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputFile::FileId(file_id) => f.debug_tuple("FileId").field(file_id).finish(),
+            InputFile::Url(url) => f.debug_tuple("Url").field(url).finish(),
+            InputFile::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            InputFile::Memory {
+                data,
+                file_name,
+                mime,
+            } => f
+                .debug_struct("Memory")
+                .field("len", &data.len())
+                .field("file_name", file_name)
+                .field("mime", mime)
+                .finish(),
+            InputFile::Reader {
+                file_name, mime, ..
+            } => f
+                .debug_struct("Reader")
+                .field("file_name", file_name)
+                .field("mime", mime)
+                .finish_non_exhaustive(),
+            InputFile::Attach(name) => f.debug_tuple("Attach").field(name).finish(),
+        }
+    }
+}
+
+impl InputFile {
+    pub fn file_id<S: Into<String>>(file_id: S) -> Self {
+        InputFile::FileId(file_id.into())
+    }
+
+    pub fn url<S: Into<String>>(url: S) -> Self {
+        InputFile::Url(url.into())
+    }
+
+    pub fn path<P: Into<PathBuf>>(path: P) -> Self {
+        InputFile::Path(path.into())
+    }
+
+    pub fn memory<S: Into<String>, D: Into<Vec<u8>>>(file_name: S, data: D) -> Self {
+        InputFile::Memory {
+            data: data.into(),
+            file_name: file_name.into(),
+            mime: None,
+        }
+    }
+
+    pub fn reader<S: Into<String>, R: AsyncRead + Send + Unpin + 'static>(
+        file_name: S,
+        reader: R,
+    ) -> Self {
+        InputFile::Reader {
+            reader: FileReader::new(reader),
+            file_name: file_name.into(),
+            mime: None,
+            len: None,
+        }
+    }
+
+    /// Sets the length of reader content, which is otherwise uploaded chunked.
+    pub fn with_len(mut self, len: u64) -> Self {
+        if let InputFile::Reader { len: l, .. } = &mut self {
+            *l = Some(len);
+        }
+        self
+    }
+
+    /// Sets the MIME type of in-memory or reader content, otherwise guessed from the file name.
+    pub fn with_mime<S: Into<String>>(mut self, mime: S) -> Self {
+        if let InputFile::Memory { mime: m, .. } | InputFile::Reader { mime: m, .. } = &mut self {
+            *m = Some(mime.into());
+        }
+        self
+    }
+
+    /// Whether the content has to be uploaded in a multipart request.
+    pub fn needs_upload(&self) -> bool {
+        matches!(
+            self,
+            InputFile::Path(_) | InputFile::Memory { .. } | InputFile::Reader { .. }
+        )
+    }
+
+    /// The value sent for a file that is not uploaded.
+    fn as_text(&self) -> Option<String> {
+        match self {
+            InputFile::FileId(text) | InputFile::Url(text) => Some(text.clone()),
+            InputFile::Attach(name) => Some(format!("attach://{}", name)),
+            _ => None,
+        }
+    }
+
+    async fn into_part(self) -> Result<FilePart> {
+        let guess = |file_name: &str| {
+            mime_guess::from_path(file_name)
+                .first()
+                .map(|m| m.to_string())
+        };
+        match self {
+            InputFile::Path(path) => {
+                let metadata = tokio::fs::metadata(&path)
+                    .await
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                if !metadata.is_file() {
+                    anyhow::bail!("{} is not a file", path.display());
+                }
+                Ok(FilePart {
+                    file_name: path
+                        .file_name()
+                        .and_then(|f| f.to_str())
+                        .map(|f| f.to_string()),
+                    mime: mime_guess::from_path(&path).first().map(|m| m.to_string()),
+                    data: FileData::Path(path),
+                })
+            }
+            InputFile::Memory {
+                data,
+                file_name,
+                mime,
+            } => Ok(FilePart {
+                mime: mime.or_else(|| guess(&file_name)),
+                file_name: Some(file_name),
+                data: FileData::Bytes(data),
+            }),
+            InputFile::Reader {
+                reader,
+                file_name,
+                mime,
+                len,
+            } => Ok(FilePart {
+                mime: mime.or_else(|| guess(&file_name)),
+                file_name: Some(file_name),
+                data: FileData::Reader { reader, len },
+            }),
+            other => anyhow::bail!("{:?} is not uploaded", other),
+        }
+    }
+
+    /// Adds the file to `form` under `key`, uploading it if needed.
+    pub(crate) async fn add_to_form(self, key: &str, form: Form) -> Result<Form> {
+        match self.as_text() {
+            Some(text) => Ok(form.text(key, text)),
+            None => Ok(form.part(key, Part::File(self.into_part().await?))),
+        }
+    }
+
+    /// Uploads the file under a random name and replaces it with an `attach://` reference,
+    /// for files nested in json fields such as `InputMedia`.
+    pub(crate) async fn attach(&mut self, form: Form) -> Result<Form> {
+        if !self.needs_upload() {
+            return Ok(form);
+        }
+        let name = random_attach_name();
+        let file = std::mem::replace(self, InputFile::Attach(name.clone()));
+        Ok(form.part(name, Part::File(file.into_part().await?)))
+    }
+}
+
+fn random_attach_name() -> String {
+    let mut rng = rand::thread_rng();
+    (0..16)
+        .map(|_| rng.gen_range(b'a'..=b'z') as char)
+        .collect()
+}
+
+impl From<PathBuf> for InputFile {
+    fn from(path: PathBuf) -> Self {
+        InputFile::Path(path)
+    }
+}
+
+impl From<&Path> for InputFile {
+    fn from(path: &Path) -> Self {
+        InputFile::Path(path.to_path_buf())
+    }
+}
+
+impl Serialize for InputFile {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.as_text() {
+            Some(text) => serializer.serialize_str(&text),
+            None => Err(serde::ser::Error::custom(
+                "a file to upload can only be sent in a multipart request",
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for InputFile {
+    fn deserialize<D>(deserializer: D) -> Result<InputFile, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        Ok(match text.strip_prefix("attach://") {
+            Some(name) => InputFile::Attach(name.to_string()),
+            None if text.starts_with("http://") || text.starts_with("https://") => {
+                InputFile::Url(text)
+            }
+            None => InputFile::FileId(text),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum InputMedia {
     #[serde(rename = "photo")]
     Photo {
-        media: String,
+        media: InputFile,
         #[serde(skip_serializing_if = "Option::is_none")]
         caption: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -1595,9 +1928,9 @@ pub enum InputMedia {
     },
     #[serde(rename = "video")]
     Video {
-        media: String,
+        media: InputFile,
         #[serde(skip_serializing_if = "Option::is_none")]
-        thumbnail: Option<String>,
+        thumbnail: Option<InputFile>,
         #[serde(skip_serializing_if = "Option::is_none")]
         caption: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -1619,9 +1952,9 @@ pub enum InputMedia {
     },
     #[serde(rename = "animation")]
     Animation {
-        media: String,
+        media: InputFile,
         #[serde(skip_serializing_if = "Option::is_none")]
-        thumbnail: Option<String>,
+        thumbnail: Option<InputFile>,
         #[serde(skip_serializing_if = "Option::is_none")]
         caption: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -1641,9 +1974,9 @@ pub enum InputMedia {
     },
     #[serde(rename = "audio")]
     Audio {
-        media: String,
+        media: InputFile,
         #[serde(skip_serializing_if = "Option::is_none")]
-        thumbnail: Option<String>,
+        thumbnail: Option<InputFile>,
         #[serde(skip_serializing_if = "Option::is_none")]
         caption: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -1660,9 +1993,9 @@ pub enum InputMedia {
 
     #[serde(rename = "document")]
     Document {
-        media: String,
+        media: InputFile,
         #[serde(skip_serializing_if = "Option::is_none")]
-        thumbnail: Option<String>,
+        thumbnail: Option<InputFile>,
         #[serde(skip_serializing_if = "Option::is_none")]
         caption: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -1675,34 +2008,9 @@ pub enum InputMedia {
 }
 
 impl InputMedia {
-    pub(crate) async fn try_part(
-        &mut self,
-        mut form: reqwest::multipart::Form,
-    ) -> anyhow::Result<reqwest::multipart::Form> {
+    pub(crate) async fn try_part(&mut self, mut form: Form) -> anyhow::Result<Form> {
         match self {
-            Self::Photo { media, .. } => {
-                let media_clone = media.clone();
-                let path = std::path::Path::new(&media_clone);
-                if path.is_file() {
-                    let filename = path
-                        .file_name()
-                        .and_then(|f| f.to_str())
-                        .and_then(|f| Some(f.to_string()))
-                        .unwrap_or_else(|| {
-                            // 生成一个16位的随机字符串
-                            let mut rng = rand::thread_rng();
-                            let mut filename = Vec::with_capacity(16);
-                            for _ in 0..16 {
-                                filename.push(rng.gen_range(b'a'..=b'z') as char);
-                            }
-                            filename.into_iter().collect::<String>()
-                        });
-                    *media = format!("attach://{}", filename);
-                    form = file_to_multipart(filename, path, form).await?;
-                }
-
-                Ok(form)
-            }
+            InputMedia::Photo { media, .. } => media.attach(form).await,
             InputMedia::Video {
                 media, thumbnail, ..
             }
@@ -1715,45 +2023,9 @@ impl InputMedia {
             | InputMedia::Document {
                 media, thumbnail, ..
             } => {
-                let media_clone = media.clone();
-                let path = std::path::Path::new(&media_clone);
-                if path.is_file() {
-                    let filename = path
-                        .file_name()
-                        .and_then(|f| f.to_str())
-                        .and_then(|f| Some(f.to_string()))
-                        .unwrap_or_else(|| {
-                            // 生成一个16位的随机字符串
-                            let mut rng = rand::thread_rng();
-                            let mut filename = Vec::with_capacity(16);
-                            for _ in 0..16 {
-                                filename.push(rng.gen_range(b'a'..=b'z') as char);
-                            }
-                            filename.into_iter().collect::<String>()
-                        });
-                    *media = format!("attach://{}", filename);
-                    form = file_to_multipart(filename, path, form).await?;
-                }
+                form = media.attach(form).await?;
                 if let Some(thumbnail) = thumbnail {
-                    let thumbnail_clone = thumbnail.clone();
-                    let path = std::path::Path::new(&thumbnail_clone);
-                    if path.is_file() {
-                        let filename = path
-                            .file_name()
-                            .and_then(|f| f.to_str())
-                            .and_then(|f| Some(f.to_string()))
-                            .unwrap_or_else(|| {
-                                // 生成一个16位的随机字符串
-                                let mut rng = rand::thread_rng();
-                                let mut filename = Vec::with_capacity(16);
-                                for _ in 0..16 {
-                                    filename.push(rng.gen_range(b'a'..=b'z') as char);
-                                }
-                                filename.into_iter().collect::<String>()
-                            });
-                        *thumbnail = format!("attach://{}", filename);
-                        form = file_to_multipart(filename, path, form).await?;
-                    }
+                    form = thumbnail.attach(form).await?;
                 }
                 Ok(form)
             }
@@ -1764,12 +2036,12 @@ impl InputMedia {
 #[serde(tag = "type")]
 pub enum InputPaidMedia {
     #[serde(rename = "photo")]
-    Photo { media: String },
+    Photo { media: InputFile },
     #[serde(rename = "video")]
     Video {
-        media: String,
+        media: InputFile,
         #[serde(skip_serializing_if = "Option::is_none")]
-        thumbnail: Option<String>,
+        thumbnail: Option<InputFile>,
         #[serde(skip_serializing_if = "Option::is_none")]
         width: Option<i64>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -1782,74 +2054,15 @@ pub enum InputPaidMedia {
 }
 
 impl InputPaidMedia {
-    pub(crate) async fn try_part(
-        &mut self,
-        mut form: reqwest::multipart::Form,
-    ) -> anyhow::Result<reqwest::multipart::Form> {
+    pub(crate) async fn try_part(&mut self, mut form: Form) -> anyhow::Result<Form> {
         match self {
-            InputPaidMedia::Photo { media } => {
-                let path = Path::new(media);
-                if path.exists() {
-                    let filename = path
-                        .file_name()
-                        .and_then(|f| f.to_str())
-                        .and_then(|f| Some(f.to_string()))
-                        .unwrap_or_else(|| {
-                            // 生成一个16位的随机字符串
-                            let mut rng = rand::thread_rng();
-                            let mut filename = Vec::with_capacity(16);
-                            for _ in 0..16 {
-                                filename.push(rng.gen_range(b'a'..=b'z') as char);
-                            }
-                            filename.into_iter().collect::<String>()
-                        });
-                    form = file_to_multipart(filename.clone(), path, form).await?;
-                    *media = format!("attach://{}", filename);
-                }
-                Ok(form)
-            }
+            InputPaidMedia::Photo { media } => media.attach(form).await,
             InputPaidMedia::Video {
                 media, thumbnail, ..
             } => {
-                let media_clone = media.clone();
-                let path = std::path::Path::new(&media_clone);
-                if path.is_file() {
-                    let filename = path
-                        .file_name()
-                        .and_then(|f| f.to_str())
-                        .and_then(|f| Some(f.to_string()))
-                        .unwrap_or_else(|| {
-                            // 生成一个16位的随机字符串
-                            let mut rng = rand::thread_rng();
-                            let mut filename = Vec::with_capacity(16);
-                            for _ in 0..16 {
-                                filename.push(rng.gen_range(b'a'..=b'z') as char);
-                            }
-                            filename.into_iter().collect::<String>()
-                        });
-                    *media = format!("attach://{}", filename);
-                    form = file_to_multipart(filename, path, form).await?;
-                }
+                form = media.attach(form).await?;
                 if let Some(thumbnail) = thumbnail {
-                    let thumbnail_clone = thumbnail.clone();
-                    let path = std::path::Path::new(&thumbnail_clone);
-                    if path.is_file() {
-                        let filename = path
-                            .file_name()
-                            .and_then(|f| f.to_str())
-                            .and_then(|f| Some(f.to_string()))
-                            .unwrap_or_else(|| {
-                                // 生成一个16位的随机字符串
-                                let mut rng = rand::thread_rng();
-                                let mut filename = Vec::with_capacity(16);
-                                for _ in 0..16 {
-                                    filename.push(rng.gen_range(b'a'..=b'z') as char);
-                                }
-                                filename.into_iter().collect::<String>()
-                            });
-                        *thumbnail = format!("attach://{}", filename);
-                        form = file_to_multipart(filename, path, form).await?;
-                    }
+                    form = thumbnail.attach(form).await?;
                 }
                 Ok(form)
             }
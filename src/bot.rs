@@ -1,17 +1,88 @@
-use std::sync::Arc;
+use std::{
+    fmt,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use anyhow::Result;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::broadcast::Sender;
 
-use crate::{getting_updates::types::UpdateData, utils::ToMultipart};
+use crate::{
+    available_types::{File, ResponseParameters},
+    error::{ApiError, Result},
+    getting_updates::types::Update,
+    middleware::{Middleware, Next},
+    progress::{self, ProgressHandler, UploadProgress},
+    rate_limit::RateLimiter,
+    telegram_method::TelegramMethod,
+    token::{redact_url, Token},
+    transport::{ApiRequest, Form, RequestBody, ReqwestTransport, Transport},
+    upload_cache::{self, UploadCache},
+};
 
 #[derive(Debug, Clone)]
 pub struct Bot {
-    pub token: String,
-    pub client: reqwest::Client,
-    pub sender: Arc<Sender<UpdateData>>,
+    pub token: Token,
+    pub transport: Arc<dyn Transport>,
+    pub sender: Arc<Sender<Update>>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub rate_limiter: Option<RateLimiter>,
+    pub middlewares: Vec<Arc<dyn Middleware>>,
+    pub upload_progress: Option<ProgressHandler>,
+    pub upload_cache: Option<Arc<dyn UploadCache>>,
+    /// Base url of the Bot API server, e.g. a self-hosted `telegram-bot-api`.
+    pub api_url: String,
+    /// Send requests to the test environment (`/bot<token>/test/<method>`).
+    pub test_environment: bool,
+}
+
+pub const DEFAULT_API_URL: &str = "https://api.telegram.org";
+const DEFAULT_CHANNEL_CAPACITY: usize = 32;
+
+/// Where the content of a [`File`] can be fetched from.
+#[derive(Clone, PartialEq, Eq)]
+pub enum FileLocation {
+    /// Download url; contains the bot token, which `Debug` masks.
+    Url(String),
+    /// Absolute path returned by a Bot API server running in `--local` mode.
+    Local(PathBuf),
+}
+
+impl fmt::Debug for FileLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileLocation::Url(url) => f.debug_tuple("Url").field(&redact_url(url)).finish(),
+            FileLocation::Local(path) => f.debug_tuple("Local").field(path).finish(),
+        }
+    }
+}
+
+/// Resend requests rejected by flood control after the `retry_after` Telegram asks for,
+/// and requests that failed with a 5xx error after `server_error_delay`.
+///
+/// Requests uploading an [`InputFile::Reader`](crate::available_types::InputFile) are not
+/// resent: the reader was consumed by the first attempt, which is reported as the error.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Maximum time spent sleeping across all retries of one request.
+    pub max_total_wait: Duration,
+    /// Delay before resending after a 5xx error; `None` returns those errors right away.
+    pub server_error_delay: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            max_total_wait: Duration::from_secs(60),
+            server_error_delay: Some(Duration::from_secs(1)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,106 +91,460 @@ pub struct CallApiResp {
     pub result: Option<Value>,
     pub error_code: Option<i64>,
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<ResponseParameters>,
+}
+
+impl CallApiResp {
+    pub fn into_result<D: DeserializeOwned>(self) -> Result<D> {
+        match self.ok {
+            true => match self.result {
+                Some(result) => Ok(serde_json::from_value(result)?),
+                None => Err(ApiError::Decode(serde::de::Error::custom(
+                    "missing field `result`",
+                ))),
+            },
+            false => Err(ApiError::Api {
+                error_code: self.error_code.unwrap_or(0),
+                description: self.description.unwrap_or("No description".to_string()),
+                parameters: self.parameters,
+            }),
+        }
+    }
+}
+
+/// Configures the http client and the rest of a [`Bot`] before creating it.
+#[derive(Debug)]
+pub struct BotBuilder {
+    token: Token,
+    client: Option<reqwest::Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    user_agent: Option<String>,
+    api_url: String,
+    test_environment: bool,
+    channel_capacity: usize,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    upload_cache: Option<Arc<dyn UploadCache>>,
+}
+
+impl BotBuilder {
+    pub fn new<T: Into<Token>>(token: T) -> Self {
+        Self {
+            token: token.into(),
+            client: None,
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            user_agent: None,
+            api_url: DEFAULT_API_URL.to_string(),
+            test_environment: false,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            retry_policy: None,
+            rate_limiter: None,
+            middlewares: Vec::new(),
+            upload_cache: None,
+        }
+    }
+
+    /// Timeout of a whole request, 30 seconds by default. `getUpdates` calls get their
+    /// long polling `timeout` added on top of it; multipart uploads are exempt.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Only used when the client is built by the builder.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// HTTP(S) proxy, or SOCKS5 with the `socks` feature. Only used when the
+    /// client is built by the builder.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Only used when the client is built by the builder.
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Uses an existing client instead of building one. Configure `connect_timeout`,
+    /// `proxy` and `user_agent` on that client; setting them here too fails `build`.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn api_url<S: Into<String>>(mut self, api_url: S) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
+    pub fn test_environment(mut self, test_environment: bool) -> Self {
+        self.test_environment = test_environment;
+        self
+    }
+
+    /// Capacity of the broadcast channel updates are sent to, 32 by default; must not be 0.
+    pub fn channel_capacity(mut self, channel_capacity: usize) -> Self {
+        self.channel_capacity = channel_capacity;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    pub fn upload_cache<C: UploadCache + 'static>(mut self, upload_cache: C) -> Self {
+        self.upload_cache = Some(Arc::new(upload_cache));
+        self
+    }
+
+    pub fn build(self) -> Result<Bot> {
+        if self.channel_capacity == 0 {
+            return Err(ApiError::InvalidConfig(
+                "channel_capacity must be greater than 0",
+            ));
+        }
+        let client = match self.client {
+            Some(_)
+                if self.connect_timeout.is_some()
+                    || self.proxy.is_some()
+                    || self.user_agent.is_some() =>
+            {
+                return Err(ApiError::InvalidConfig(
+                    "connect_timeout, proxy and user_agent cannot be combined with client",
+                ));
+            }
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                builder.build()?
+            }
+        };
+        let mut transport = ReqwestTransport::new(client);
+        if let Some(timeout) = self.timeout {
+            transport.timeout = Some(timeout);
+        }
+        let (sender, _) = tokio::sync::broadcast::channel(self.channel_capacity);
+        Ok(Bot {
+            token: self.token,
+            transport: Arc::new(transport),
+            sender: Arc::new(sender),
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            middlewares: self.middlewares,
+            upload_progress: None,
+            upload_cache: self.upload_cache,
+            api_url: DEFAULT_API_URL.to_string(),
+            test_environment: self.test_environment,
+        }
+        .with_api_url(self.api_url))
+    }
 }
 
 impl Bot {
-    pub fn new<S: Into<String>>(token: S) -> Bot {
-        let (sender, _) = tokio::sync::broadcast::channel(32);
+    pub fn builder<T: Into<Token>>(token: T) -> BotBuilder {
+        BotBuilder::new(token)
+    }
+
+    pub fn new<T: Into<Token>>(token: T) -> Bot {
+        let (sender, _) = tokio::sync::broadcast::channel(DEFAULT_CHANNEL_CAPACITY);
         Bot {
             token: token.into(),
-            client: reqwest::Client::new(),
+            transport: Arc::new(ReqwestTransport::default()),
             sender: Arc::new(sender),
+            retry_policy: None,
+            rate_limiter: None,
+            middlewares: Vec::new(),
+            upload_progress: None,
+            upload_cache: None,
+            api_url: DEFAULT_API_URL.to_string(),
+            test_environment: false,
+        }
+    }
+
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Appends a middleware; the first one added is the outermost.
+    pub fn with_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Reports the progress of file uploads; set it on a clone of the bot to track a single call.
+    pub fn with_upload_progress<F: Fn(UploadProgress) + Send + Sync + 'static>(
+        mut self,
+        callback: F,
+    ) -> Self {
+        self.upload_progress = Some(ProgressHandler::new(callback));
+        self
+    }
+
+    /// Reuses the `file_id` of files that were uploaded before, see [`UploadCache`].
+    pub fn with_upload_cache<C: UploadCache + 'static>(mut self, upload_cache: C) -> Self {
+        self.upload_cache = Some(Arc::new(upload_cache));
+        self
+    }
+
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    pub fn with_api_url<S: Into<String>>(mut self, api_url: S) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_test_environment(mut self, test_environment: bool) -> Self {
+        self.test_environment = test_environment;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    fn env_segment(&self) -> &'static str {
+        if self.test_environment {
+            "/test"
+        } else {
+            ""
         }
     }
 
     pub(crate) fn format_url(&self, method: &str) -> String {
         format!(
-            "https://api.telegram.org/bot{}/{}",
-            urlencoding::encode(&self.token),
+            "{}/bot{}{}/{}",
+            self.api_url,
+            urlencoding::encode(self.token.expose()),
+            self.env_segment(),
             method
         )
     }
 
-    pub(crate) async fn call_api_no_payload<D: for<'a> Deserialize<'a> + DeserializeOwned>(
+    pub fn file_url(&self, file_path: &str) -> String {
+        format!(
+            "{}/file/bot{}{}/{}",
+            self.api_url,
+            urlencoding::encode(self.token.expose()),
+            self.env_segment(),
+            file_path.trim_start_matches('/')
+        )
+    }
+
+    /// Returns `None` if Telegram did not include a `file_path` for the file.
+    pub fn file_location(&self, file: &File) -> Option<FileLocation> {
+        let file_path = file.file_path.as_ref()?;
+        if Path::new(file_path).is_absolute() {
+            Some(FileLocation::Local(PathBuf::from(file_path)))
+        } else {
+            Some(FileLocation::Url(self.file_url(file_path)))
+        }
+    }
+
+    async fn with_retry<D, F, Fut>(&self, function: &str, mut call: F) -> Result<D>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<D>>,
+    {
+        let mut attempt = 1;
+        let mut waited = Duration::ZERO;
+        loop {
+            let err = match call().await {
+                Ok(result) => return Ok(result),
+                Err(err) => err,
+            };
+            let Some(policy) = &self.retry_policy else {
+                return Err(err);
+            };
+            let server_error = err.error_code().is_some_and(|code| code >= 500);
+            let retry_after = match (err.retry_after(), server_error) {
+                (Some(retry_after), _) => retry_after,
+                (None, true) => match policy.server_error_delay {
+                    Some(delay) => delay,
+                    None => return Err(err),
+                },
+                (None, false) => return Err(err),
+            };
+            if attempt >= policy.max_attempts || waited + retry_after > policy.max_total_wait {
+                return Err(err);
+            }
+            tracing::warn!(
+                "{} on {}, retrying in {:?} (attempt {}/{})",
+                match server_error {
+                    true => "Server error",
+                    false => "Flood control",
+                },
+                function,
+                retry_after,
+                attempt + 1,
+                policy.max_attempts
+            );
+            tokio::time::sleep(retry_after).await;
+            waited += retry_after;
+            attempt += 1;
+        }
+    }
+
+    pub(crate) async fn send_request<D: DeserializeOwned>(
         &self,
         function: &str,
+        body: RequestBody,
     ) -> Result<D> {
-        let url = self.format_url(function);
-        let resp = self
-            .client
-            .get(&url)
-            .send()
-            .await?
-            .json::<CallApiResp>()
-            .await?;
-        match resp.ok {
-            true => match resp.result {
-                Some(resp) => Ok(serde_json::from_value(resp)?),
-                None => Err(anyhow::anyhow!("Failed to call api: No result")),
-            },
-            false => {
-                let description = resp.description.unwrap_or("No description".to_string());
-                Err(anyhow::anyhow!("Failed to call api: {}", description))
+        let (Some(cache), Some(field), RequestBody::Multipart(form)) = (
+            &self.upload_cache,
+            upload_cache::cached_field(function),
+            &body,
+        ) else {
+            return self.dispatch(function, body).await?.into_result();
+        };
+        let bot_id = self.token.expose().split(':').next().unwrap_or_default();
+        let Some(key) = upload_cache::cache_key(bot_id, field, form).await? else {
+            return self.dispatch(function, body).await?.into_result();
+        };
+        if let Some(file_id) = cache.get(&key).await {
+            let cached = upload_cache::with_file_id(form, field, &file_id);
+            let resp = self
+                .dispatch(function, RequestBody::Multipart(cached))
+                .await?;
+            // A file_id Telegram no longer accepts is forgotten and the file uploaded again.
+            // Other errors, like a missing chat, would fail the upload just the same.
+            let stale = resp.error_code == Some(400)
+                && resp
+                    .description
+                    .as_deref()
+                    .is_some_and(upload_cache::is_file_id_error);
+            if !stale {
+                return resp.into_result();
             }
+            cache.remove(&key).await;
+        }
+        let resp = self.dispatch(function, body).await?;
+        if let Some(file_id) = resp
+            .result
+            .as_ref()
+            .and_then(|message| upload_cache::sent_file_id(field, message))
+        {
+            cache.put(&key, file_id).await;
         }
+        resp.into_result()
     }
 
-    pub(crate) async fn call_api_json<
-        D: for<'a> Deserialize<'a> + DeserializeOwned,
-        S: Serialize,
-    >(
+    /// Sends one request through the middlewares, then the rate limiter.
+    async fn dispatch(&self, function: &str, body: RequestBody) -> Result<CallApiResp> {
+        let request = ApiRequest {
+            method: function.to_string(),
+            url: self.format_url(function),
+            body,
+        };
+        Next::new(self).run(request).await
+    }
+
+    /// End of the middleware chain. Waiting for the rate limiter and tracking the
+    /// upload happen here, so neither counts time spent in middlewares.
+    pub(crate) async fn transmit(&self, request: ApiRequest) -> Result<CallApiResp> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if RateLimiter::applies_to(&request.method) {
+                rate_limiter
+                    .acquire(request.body.chat_id().as_deref())
+                    .await;
+            }
+        }
+        let request = match (request.body, &self.upload_progress) {
+            (RequestBody::Multipart(form), Some(handler)) => ApiRequest {
+                body: RequestBody::Multipart(
+                    progress::track(form, &request.method, handler).await?,
+                ),
+                ..request
+            },
+            (body, _) => ApiRequest { body, ..request },
+        };
+        self.transport.send(request).await
+    }
+
+    pub(crate) async fn call_api_no_payload<D: DeserializeOwned>(
         &self,
         function: &str,
-        payload: &S,
     ) -> Result<D> {
-        let url = self.format_url(function);
-        let resp = self
-            .client
-            .get(&url)
-            .json(payload)
-            .send()
-            .await?
-            .json::<CallApiResp>()
-            .await?;
-        match resp.ok {
-            true => match resp.result {
-                Some(resp) => Ok(serde_json::from_value(resp)?),
-                None => Err(anyhow::anyhow!("Failed to call api: No result")),
-            },
-            false => {
-                let description = resp.description.unwrap_or("No description".to_string());
-                Err(anyhow::anyhow!("Failed to call api: {}", description))
-            }
-        }
+        self.with_retry(function, || self.send_request(function, RequestBody::Empty))
+            .await
     }
 
-    pub(crate) async fn call_api_multipart<
-        D: for<'a> Deserialize<'a> + DeserializeOwned,
-        M: ToMultipart,
-    >(
+    pub(crate) async fn call_api_json<D: DeserializeOwned, S: Serialize>(
         &self,
         function: &str,
-        payload: M,
+        payload: &S,
     ) -> Result<D> {
-        let url = self.format_url(function);
-        let resp = self
-            .client
-            .post(&url)
-            .multipart(payload.to_multipart().await?)
-            .send()
-            .await?
-            .json::<CallApiResp>()
-            .await?;
-
-        match resp.ok {
-            true => match resp.result {
-                Some(resp) => Ok(serde_json::from_value(resp)?),
-                None => Err(anyhow::anyhow!("Failed to call api: No result")),
-            },
-            false => {
-                let description = resp.description.unwrap_or("No description".to_string());
-                Err(anyhow::anyhow!("Failed to call api: {}", description))
-            }
-        }
+        let payload = serde_json::to_value(payload).map_err(|e| ApiError::Payload(e.into()))?;
+        self.with_retry(function, || {
+            self.send_request(function, RequestBody::Json(payload.clone()))
+        })
+        .await
+    }
+
+    /// Sends any payload implementing [`TelegramMethod`] to its method.
+    pub async fn execute<M: TelegramMethod>(&self, payload: &M) -> Result<M::Response> {
+        // The body is rebuilt on every attempt, since a multipart form can only be sent once.
+        self.with_retry(M::NAME, || async {
+            let body = payload.to_body().await?;
+            self.send_request(M::NAME, body).await
+        })
+        .await
+    }
+
+    /// Calls any Bot API method, including ones this crate does not wrap yet.
+    pub async fn call_raw(&self, method: &str, params: Value) -> Result<Value> {
+        self.call_api_json(method, &params).await
+    }
+
+    /// Typed variant of [`Bot::call_raw`].
+    pub async fn call_typed<D: DeserializeOwned, S: Serialize>(
+        &self,
+        method: &str,
+        params: &S,
+    ) -> Result<D> {
+        self.call_api_json(method, params).await
+    }
+
+    /// Multipart variant of [`Bot::call_raw`] for methods that upload files.
+    pub async fn call_multipart<D: DeserializeOwned>(&self, method: &str, form: Form) -> Result<D> {
+        self.with_retry(method, || {
+            self.send_request(method, RequestBody::Multipart(form.clone()))
+        })
+        .await
     }
 }
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use futures_util::StreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    available_methods::payload::GetFilePayload,
+    available_types::File,
+    bot::{Bot, FileLocation, DEFAULT_API_URL},
+    error::{ApiError, Result},
+    transport::ByteStream,
+};
+
+/// Largest file the cloud Bot API server lets bots download.
+pub const CLOUD_DOWNLOAD_LIMIT: u64 = 20 * 1024 * 1024;
+
+impl Bot {
+    /// Streams the content of `file`, calling `getFile` first if it has no `file_path`.
+    ///
+    /// Absolute paths returned by a Bot API server in `--local` mode are read from disk.
+    pub async fn download_file_stream(&self, file: &File) -> Result<ByteStream> {
+        self.check_download_size(file)?;
+        let file = match file.file_path {
+            Some(_) => file.clone(),
+            None => {
+                let file = self
+                    .get_file(&GetFilePayload {
+                        file_id: file.file_id.clone(),
+                    })
+                    .await?;
+                self.check_download_size(&file)?;
+                file
+            }
+        };
+        match self.file_location(&file).ok_or(ApiError::MissingFilePath)? {
+            FileLocation::Local(path) => {
+                let reader = tokio::fs::File::open(path).await?;
+                Ok(ReaderStream::new(reader)
+                    .map(|chunk| chunk.map_err(ApiError::from))
+                    .boxed())
+            }
+            FileLocation::Url(url) => self.transport.download(&url).await,
+        }
+    }
+
+    /// Writes the content of `file` to `writer` and returns the number of bytes written.
+    pub async fn download_file<W: AsyncWrite + Unpin + ?Sized>(
+        &self,
+        file: &File,
+        writer: &mut W,
+    ) -> Result<u64> {
+        let mut stream = self.download_file_stream(file).await?;
+        let mut written = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// Saves the content of `file` to `path`, removing the partial file if the download fails.
+    pub async fn download_file_to_path<P: AsRef<Path>>(&self, file: &File, path: P) -> Result<u64> {
+        let path = path.as_ref();
+        let mut output = tokio::fs::File::create(path).await?;
+        let result = self.download_file(file, &mut output).await;
+        if result.is_err() {
+            drop(output);
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        result
+    }
+
+    pub async fn download_file_by_id<W: AsyncWrite + Unpin + ?Sized>(
+        &self,
+        file_id: &str,
+        writer: &mut W,
+    ) -> Result<u64> {
+        let file = self
+            .get_file(&GetFilePayload {
+                file_id: file_id.to_string(),
+            })
+            .await?;
+        self.download_file(&file, writer).await
+    }
+
+    fn check_download_size(&self, file: &File) -> Result<()> {
+        let file_size = file.file_size.unwrap_or(0).max(0) as u64;
+        if self.api_url == DEFAULT_API_URL && file_size > CLOUD_DOWNLOAD_LIMIT {
+            return Err(ApiError::FileTooBig {
+                file_size,
+                limit: CLOUD_DOWNLOAD_LIMIT,
+            });
+        }
+        Ok(())
+    }
+}
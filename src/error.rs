@@ -0,0 +1,151 @@
+use std::{fmt, time::Duration};
+
+use crate::available_types::ResponseParameters;
+
+pub type Result<T> = std::result::Result<T, ApiError>;
+
+/// Error returned by `Bot` api calls, polling, the builder and the file-backed stores.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request could not be sent or the response could not be read.
+    Transport(reqwest::Error),
+    /// The response was not the json Telegram is expected to return.
+    Decode(serde_json::Error),
+    /// Telegram answered with `ok: false`.
+    Api {
+        error_code: i64,
+        description: String,
+        parameters: Option<ResponseParameters>,
+    },
+    /// The request body could not be built, e.g. a local file could not be read.
+    Payload(anyhow::Error),
+    /// `getFile` did not return a `file_path` to download the file from.
+    MissingFilePath,
+    /// The file is larger than the Bot API server allows to download.
+    FileTooBig { file_size: u64, limit: u64 },
+    /// Reading or writing a local file failed, e.g. a download or a persisted cache.
+    Io(std::io::Error),
+    /// [`BotBuilder`](crate::bot::BotBuilder) options that cannot be used together.
+    InvalidConfig(&'static str),
+    /// An [`UpdateDelivery::Handler`](crate::getting_updates::UpdateDelivery::Handler)
+    /// panicked while processing an update.
+    HandlerPanicked(String),
+}
+
+impl ApiError {
+    pub fn error_code(&self) -> Option<i64> {
+        match self {
+            ApiError::Api { error_code, .. } => Some(*error_code),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            ApiError::Api { description, .. } => Some(description),
+            _ => None,
+        }
+    }
+
+    pub fn parameters(&self) -> Option<&ResponseParameters> {
+        match self {
+            ApiError::Api { parameters, .. } => parameters.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Time Telegram asks to wait before repeating the request (flood control).
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.parameters()
+            .and_then(|p| p.retry_after)
+            .map(|secs| Duration::from_secs(secs.max(0) as u64))
+    }
+
+    /// New identifier of a group that was migrated to a supergroup.
+    pub fn migrate_to_chat_id(&self) -> Option<i64> {
+        self.parameters().and_then(|p| p.migrate_to_chat_id)
+    }
+
+    pub fn is_bot_blocked(&self) -> bool {
+        self.description_contains("bot was blocked by the user")
+    }
+
+    pub fn is_chat_not_found(&self) -> bool {
+        self.description_contains("chat not found")
+    }
+
+    pub fn is_message_not_modified(&self) -> bool {
+        self.description_contains("message is not modified")
+    }
+
+    fn description_contains(&self, pattern: &str) -> bool {
+        self.description()
+            .map(|d| d.to_lowercase().contains(pattern))
+            .unwrap_or(false)
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Transport(e) => write!(f, "Failed to call api: {}", e),
+            ApiError::Decode(e) => write!(f, "Failed to decode api response: {}", e),
+            ApiError::Api {
+                error_code,
+                description,
+                ..
+            } => write!(f, "Failed to call api: [{}] {}", error_code, description),
+            ApiError::Payload(e) => write!(f, "Failed to build api request: {}", e),
+            ApiError::MissingFilePath => write!(f, "Failed to download file: no file_path"),
+            ApiError::FileTooBig { file_size, limit } => write!(
+                f,
+                "Failed to download file: {} bytes exceeds the limit of {} bytes",
+                file_size, limit
+            ),
+            ApiError::Io(e) => write!(f, "I/O error: {}", e),
+            ApiError::InvalidConfig(message) => write!(f, "Invalid bot configuration: {}", message),
+            ApiError::HandlerPanicked(message) => write!(f, "Update handler panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApiError::Transport(e) => Some(e),
+            ApiError::Decode(e) => Some(e),
+            ApiError::Api { .. } => None,
+            ApiError::Payload(e) => Some(e.as_ref()),
+            ApiError::MissingFilePath
+            | ApiError::FileTooBig { .. }
+            | ApiError::InvalidConfig(_)
+            | ApiError::HandlerPanicked(_) => None,
+            ApiError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        // The url contains the bot token, so it must never end up in logs.
+        ApiError::Transport(e.without_url())
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(e: serde_json::Error) -> Self {
+        ApiError::Decode(e)
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError::Payload(e)
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(e: std::io::Error) -> Self {
+        ApiError::Io(e)
+    }
+}
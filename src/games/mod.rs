@@ -2,14 +2,21 @@ use payload::*;
 use serde_json::Value;
 use types::GameHighScore;
 
+use crate::error::Result;
+use crate::telegram_method::json_methods;
 use crate::{available_types::Message, bot::Bot};
-use anyhow::Result;
 pub mod payload;
 pub mod types;
 
+json_methods! {
+    SendGamePayload => "sendGame": Message,
+    SetGameScorePayload => "setGameScore": Message,
+    GetGameHighScoresPayload => "getGameHighScores": Vec<GameHighScore>,
+}
+
 impl Bot {
     pub async fn send_game(&self, payload: &SendGamePayload) -> Result<Message> {
-        self.call_api_json("sendGame", payload).await
+        self.execute(payload).await
     }
 
     pub async fn callback_game(&self) -> Result<Value> {
@@ -17,13 +24,13 @@ impl Bot {
     }
 
     pub async fn set_game_score(&self, payload: &SetGameScorePayload) -> Result<Message> {
-        self.call_api_json("setGameScore", payload).await
+        self.execute(payload).await
     }
 
     pub async fn get_game_high_scores(
         &self,
         payload: &GetGameHighScoresPayload,
     ) -> Result<Vec<GameHighScore>> {
-        self.call_api_json("getGameHighScores", payload).await
+        self.execute(payload).await
     }
 }
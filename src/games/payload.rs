@@ -6,6 +6,7 @@ use crate::available_types::{InlineKeyboardMarkup, ReplyParameters};
 pub struct SendGamePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
+    /// Games can't be sent to channels, so unlike elsewhere this is never a `@username`.
     pub chat_id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
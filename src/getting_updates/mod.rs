@@ -1,21 +1,26 @@
+pub mod offset_store;
 pub mod payload;
+#[cfg(feature = "webhook")]
+pub mod self_signed;
 pub mod types;
 pub mod webhook;
+#[cfg(feature = "webhook")]
+pub mod webhook_server;
 
-use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast::Receiver;
-use types::{AllowedUpdateType, Update, UpdateData};
+use std::{any::Any, fmt, future::Future, panic::AssertUnwindSafe, sync::Arc, time::Duration};
 
-use crate::bot::Bot;
+use futures_util::{future::BoxFuture, FutureExt};
+use offset_store::OffsetStore;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{broadcast::Receiver, mpsc, watch},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+use types::{AllowedUpdateType, Update};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct GetUpdatesResponse {
-    ok: bool,
-    #[serde(default)]
-    result: Vec<Update>,
-    error_code: Option<i64>,
-    description: Option<String>,
-}
+use crate::{bot::Bot, error::ApiError};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GetUpdateConfig {
@@ -25,6 +30,20 @@ pub struct GetUpdateConfig {
     pub offset: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_updates: Option<Vec<AllowedUpdateType>>,
+    /// Delay between failed `getUpdates` calls; not sent to Telegram.
+    #[serde(skip)]
+    pub backoff: Backoff,
+    /// Where received updates go; not sent to Telegram.
+    #[serde(skip)]
+    pub delivery: UpdateDelivery,
+    /// Where the offset is loaded from when `offset` is `None`, and saved to after
+    /// every accepted update (see [`UpdateDelivery`]); not sent to Telegram.
+    #[serde(skip)]
+    pub offset_store: Option<Arc<dyn OffsetStore>>,
+    /// Drops the updates that are pending on startup instead of delivering them,
+    /// ignoring `offset` and the stored offset; not sent to Telegram.
+    #[serde(skip)]
+    pub skip_backlog: bool,
 }
 
 impl Default for GetUpdateConfig {
@@ -34,62 +53,423 @@ impl Default for GetUpdateConfig {
             timeout: 60,
             offset: None,
             allowed_updates: None,
+            backoff: Backoff::default(),
+            delivery: UpdateDelivery::default(),
+            offset_store: None,
+            skip_backlog: false,
         }
     }
 }
 
+type UpdateHandler = Arc<dyn Fn(Update) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// How the polling task hands updates over.
+///
+/// Only `Handler` gives at-least-once delivery: the offset advances after the handler
+/// returned, so an update interrupted by a crash is received again. `Channel` advances
+/// it as soon as the update is buffered, and updates still sitting in the channel are
+/// lost on a crash. Broadcast subscribers still receive a copy of every accepted update.
+#[derive(Clone, Default)]
+pub enum UpdateDelivery {
+    /// Lossy fan-out to [`Bot::subscribe_updates`]; lagging subscribers miss updates.
+    #[default]
+    Broadcast,
+    /// Waits for room in the channel. Polling stops when the receiver is dropped.
+    /// The offset is committed once the update is buffered, not once it is processed.
+    Channel(mpsc::Sender<Update>),
+    /// Awaits the handler for every update before fetching the next one. Polling fails
+    /// with [`ApiError::HandlerPanicked`] if the handler panics.
+    Handler(UpdateHandler),
+}
+
+impl UpdateDelivery {
+    pub fn handler<F, Fut>(handler: F) -> Self
+    where
+        F: Fn(Update) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        UpdateDelivery::Handler(Arc::new(move |update| handler(update).boxed()))
+    }
+}
+
+impl fmt::Debug for UpdateDelivery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateDelivery::Broadcast => f.write_str("Broadcast"),
+            UpdateDelivery::Channel(sender) => f.debug_tuple("Channel").field(sender).finish(),
+            UpdateDelivery::Handler(_) => f.write_str("Handler"),
+        }
+    }
+}
+
+/// Exponential backoff: `initial * multiplier^(attempt - 1)`, capped at `max` and
+/// randomly spread by up to `jitter` (0.1 = ±10%). A delay that can't be computed,
+/// e.g. with a negative multiplier, is `max`.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.1,
+        }
+    }
+}
+
+impl Backoff {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exp = self
+            .multiplier
+            .powi(attempt.saturating_sub(1).min(64) as i32);
+        let delay = (self.initial.as_secs_f64() * exp).min(self.max.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = match jitter > 0.0 {
+            true => rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter),
+            false => 1.0,
+        };
+        // A negative or NaN multiplier or jitter must not panic the polling task.
+        Duration::try_from_secs_f64(delay * factor).unwrap_or(self.max)
+    }
+}
+
+/// Health of a polling task, observed through [`PollingHandle::status`].
+#[derive(Debug, Clone)]
+pub enum PollingStatus {
+    Running,
+    /// `getUpdates` failed and will be retried after `delay`.
+    Retrying {
+        attempt: u32,
+        delay: Duration,
+        error: Arc<ApiError>,
+    },
+    /// Stopped through [`PollingHandle::stop`].
+    Stopped,
+    /// Stopped on an error that retrying cannot fix: an invalid token (401/404), an
+    /// active webhook (409) or a panicking update handler. The panicked update is not
+    /// committed and is received again after a restart.
+    Failed(Arc<ApiError>),
+}
+
+fn is_fatal(error: &ApiError) -> bool {
+    match error.error_code() {
+        Some(401) | Some(404) => true,
+        // Another instance polling is a 409 too, but it goes away during rolling deploys.
+        Some(409) => error.description().is_some_and(|d| d.contains("webhook")),
+        _ => false,
+    }
+}
+
 impl Bot {
-    pub fn subscribe_updates(&self) -> Receiver<UpdateData> {
+    pub fn subscribe_updates(&self) -> Receiver<Update> {
         self.sender.subscribe()
     }
 
-    pub fn start_get_updates(&self, mut config: GetUpdateConfig) {
-        let url = self.format_url("getUpdates");
-        let client = self.client.clone();
-        let mut last_update_id: Option<i64> = None;
-        let sender = self.sender.clone();
+    /// Starts long polling in a background task; updates go where `config.delivery` says.
+    pub fn start_get_updates(&self, config: GetUpdateConfig) -> PollingHandle {
+        let cancel = CancellationToken::new();
+        let (status, status_rx) = watch::channel(PollingStatus::Running);
+        let task = tokio::spawn(self.clone().poll_updates(config, cancel.clone(), status));
+        PollingHandle {
+            bot: self.clone(),
+            cancel,
+            task,
+            status: status_rx,
+        }
+    }
 
-        tokio::spawn(async move {
-            loop {
-                if let Some(id) = last_update_id {
-                    config.offset = Some(id + 1);
+    async fn poll_updates(
+        self,
+        mut config: GetUpdateConfig,
+        cancel: CancellationToken,
+        status: watch::Sender<PollingStatus>,
+    ) -> Option<i64> {
+        if let (None, Some(store), false) =
+            (config.offset, &config.offset_store, config.skip_backlog)
+        {
+            config.offset = store.load().await;
+        }
+        let mut skip_backlog = config.skip_backlog;
+        let mut attempt = 0;
+        let mut failure = None;
+        'poll: while !cancel.is_cancelled() {
+            let result = match skip_backlog {
+                // Offset -1 returns only the latest update and forgets all earlier ones.
+                true => {
+                    let latest = GetUpdateConfig {
+                        offset: Some(-1),
+                        limit: 1,
+                        timeout: 0,
+                        ..config.clone()
+                    };
+                    self.call_api_json::<Vec<Update>, _>("getUpdates", &latest)
+                        .await
                 }
-
-                let response = client.post(&url).json(&config).send().await;
-
-                match response {
-                    Ok(response) => match response.json::<GetUpdatesResponse>().await {
-                        Ok(response) => match response.ok {
-                            true => {
-                                for update in response.result {
-                                    if let Err(e) = sender.send(update.data) {
-                                        tracing::error!(
-                                            "Failed to send update to subscriber: {}",
-                                            e
-                                        );
-                                    }
-                                    last_update_id = Some(update.update_id);
-                                }
-                            }
-                            false => {
-                                tracing::error!(
-                                    "Failed to get updates, Code: {}, Description: {}",
-                                    response.error_code.unwrap_or(0),
-                                    response.description.unwrap_or("No description".to_string())
-                                );
-                            }
-                        },
-                        Err(e) => {
-                            tracing::error!("Failed to parse getUpdates response: {}", e);
-                            continue;
+                false => {
+                    self.call_api_json::<Vec<Update>, _>("getUpdates", &config)
+                        .await
+                }
+            };
+            match result {
+                Ok(updates) => {
+                    if attempt > 0 {
+                        attempt = 0;
+                        status.send_replace(PollingStatus::Running);
+                    }
+                    if skip_backlog {
+                        skip_backlog = false;
+                        if let Some(latest) = updates.last() {
+                            tracing::info!("Skipped pending updates up to {}", latest.update_id);
+                            self.commit_offset(&mut config, latest.update_id + 1).await;
                         }
-                    },
-                    Err(e) => {
-                        tracing::error!("Failed to get updates: {}", e);
                         continue;
                     }
+                    for update in updates {
+                        let update_id = update.update_id;
+                        match self.deliver(&config.delivery, update, &cancel).await {
+                            Ok(true) => self.commit_offset(&mut config, update_id + 1).await,
+                            Ok(false) => break 'poll,
+                            // The update is not committed, so it is received again.
+                            Err(e) => {
+                                tracing::error!("Stopped getting updates: {}", e);
+                                failure = Some(e);
+                                break 'poll;
+                            }
+                        }
+                    }
+                }
+                Err(e) if is_fatal(&e) => {
+                    tracing::error!("Stopped getting updates: {}", e);
+                    status.send_replace(PollingStatus::Failed(Arc::new(e)));
+                    return config.offset;
+                }
+                Err(e) => {
+                    attempt += 1;
+                    let delay = config
+                        .backoff
+                        .delay(attempt)
+                        .max(e.retry_after().unwrap_or_default());
+                    tracing::warn!(
+                        "Failed to get updates, retrying in {:?} (attempt {}): {}",
+                        delay,
+                        attempt,
+                        e
+                    );
+                    status.send_replace(PollingStatus::Retrying {
+                        attempt,
+                        delay,
+                        error: Arc::new(e),
+                    });
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = cancel.cancelled() => {}
+                    }
                 }
             }
+        }
+
+        // Telegram only forgets updates once a request with a higher offset arrives.
+        if config.offset.is_some() {
+            let confirm = GetUpdateConfig {
+                limit: 1,
+                timeout: 0,
+                ..config.clone()
+            };
+            if let Err(e) = self
+                .call_api_json::<Vec<Update>, _>("getUpdates", &confirm)
+                .await
+            {
+                tracing::error!("Failed to confirm updates: {}", e);
+            }
+        }
+        status.send_replace(match failure {
+            Some(e) => PollingStatus::Failed(Arc::new(e)),
+            None => PollingStatus::Stopped,
         });
+        config.offset
+    }
+
+    async fn commit_offset(&self, config: &mut GetUpdateConfig, offset: i64) {
+        config.offset = Some(offset);
+        if let Some(store) = &config.offset_store {
+            store.save(offset).await;
+        }
+    }
+
+    /// Hands an update to the consumer, returning whether it was accepted.
+    async fn deliver(
+        &self,
+        delivery: &UpdateDelivery,
+        update: Update,
+        cancel: &CancellationToken,
+    ) -> Result<bool, ApiError> {
+        let copy = (self.sender.receiver_count() > 0).then(|| update.clone());
+        let accepted = match delivery {
+            UpdateDelivery::Broadcast => true,
+            UpdateDelivery::Channel(sender) => tokio::select! {
+                sent = sender.send(update) => {
+                    if sent.is_err() {
+                        tracing::warn!("Update receiver was dropped, stopped getting updates");
+                    }
+                    sent.is_ok()
+                }
+                _ = cancel.cancelled() => false,
+            },
+            UpdateDelivery::Handler(handler) => {
+                AssertUnwindSafe(handler(update))
+                    .catch_unwind()
+                    .await
+                    .map_err(|panic| ApiError::HandlerPanicked(panic_message(&*panic)))?;
+                true
+            }
+        };
+        if let (true, Some(copy)) = (accepted, copy) {
+            let _ = self.sender.send(copy);
+        }
+        Ok(accepted)
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    }
+}
+
+/// Controls the polling task started by [`Bot::start_get_updates`].
+///
+/// Dropping the handle leaves the task running.
+#[derive(Debug)]
+pub struct PollingHandle {
+    bot: Bot,
+    cancel: CancellationToken,
+    task: JoinHandle<Option<i64>>,
+    status: watch::Receiver<PollingStatus>,
+}
+
+impl PollingHandle {
+    /// Stops polling once the in-flight `getUpdates` call has finished and its updates
+    /// were delivered, then confirms them to Telegram.
+    ///
+    /// Returns the offset to continue from, if any update was received. With long
+    /// polling this can take up to the configured `timeout`.
+    pub async fn stop(self) -> Option<i64> {
+        self.cancel.cancel();
+        self.task.await.ok().flatten()
+    }
+
+    /// Stops polling and starts again with `config`, continuing from the last offset
+    /// unless `config.offset` is set. `skip_backlog` is ignored when continuing, as the
+    /// backlog was already skipped by the stopped task.
+    pub async fn restart(self, mut config: GetUpdateConfig) -> PollingHandle {
+        let bot = self.bot.clone();
+        let offset = self.stop().await;
+        if let (None, Some(offset)) = (config.offset, offset) {
+            config.offset = Some(offset);
+            config.skip_backlog = false;
+        }
+        bot.start_get_updates(config)
+    }
+
+    pub fn status(&self) -> watch::Receiver<PollingStatus> {
+        self.status.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backoff() -> Backoff {
+        Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.0,
+        }
+    }
+
+    #[test]
+    fn backoff_grows_up_to_max() {
+        let backoff = backoff();
+        let delays: Vec<_> = (1..=6)
+            .map(|attempt| backoff.delay(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 10, 10]);
+        assert_eq!(backoff.delay(u32::MAX), backoff.max);
+    }
+
+    #[test]
+    fn backoff_jitter_stays_in_range() {
+        let backoff = Backoff {
+            jitter: 0.5,
+            ..backoff()
+        };
+        for _ in 0..100 {
+            let delay = backoff.delay(3).as_secs_f64();
+            assert!((2.0..=6.0).contains(&delay), "{}", delay);
+        }
+    }
+
+    #[test]
+    fn invalid_backoff_falls_back_to_max() {
+        let negative = Backoff {
+            multiplier: -3.0,
+            ..backoff()
+        };
+        assert_eq!(negative.delay(2), negative.max);
+        let nan = Backoff {
+            multiplier: f64::NAN,
+            jitter: f64::NAN,
+            ..backoff()
+        };
+        assert_eq!(nan.delay(2), nan.max);
+        let negative_jitter = Backoff {
+            jitter: -1.0,
+            ..backoff()
+        };
+        assert_eq!(negative_jitter.delay(2), Duration::from_secs(2));
+    }
+
+    fn api_error(error_code: i64, description: &str) -> ApiError {
+        ApiError::Api {
+            error_code,
+            description: description.to_string(),
+            parameters: None,
+        }
+    }
+
+    #[test]
+    fn fatal_errors() {
+        assert!(is_fatal(&api_error(401, "Unauthorized")));
+        assert!(is_fatal(&api_error(404, "Not Found")));
+        assert!(is_fatal(&api_error(
+            409,
+            "Conflict: can't use getUpdates method while webhook is active"
+        )));
+        assert!(!is_fatal(&api_error(
+            409,
+            "Conflict: terminated by other getUpdates request"
+        )));
+        assert!(!is_fatal(&api_error(
+            429,
+            "Too Many Requests: retry after 5"
+        )));
+        assert!(!is_fatal(&api_error(500, "Internal Server Error")));
+        assert!(!is_fatal(&api_error(502, "Bad Gateway")));
+        assert!(!is_fatal(&ApiError::MissingFilePath));
     }
 }
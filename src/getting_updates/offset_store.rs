@@ -0,0 +1,124 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Remembers the `getUpdates` offset across restarts.
+///
+/// [`Bot::start_get_updates`](crate::bot::Bot::start_get_updates) loads it on startup and
+/// saves it after every accepted update. With [`UpdateDelivery::Handler`](super::UpdateDelivery::Handler)
+/// an update whose processing was interrupted by a crash is received again; with
+/// `Channel` the offset is saved once the update is buffered.
+#[async_trait::async_trait]
+pub trait OffsetStore: fmt::Debug + Send + Sync {
+    async fn load(&self) -> Option<i64>;
+    async fn save(&self, offset: i64);
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryOffsetStore {
+    offset: Mutex<Option<i64>>,
+}
+
+impl MemoryOffsetStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl OffsetStore for MemoryOffsetStore {
+    async fn load(&self) -> Option<i64> {
+        *self.offset.lock().ok()?
+    }
+
+    async fn save(&self, offset: i64) {
+        if let Ok(mut stored) = self.offset.lock() {
+            *stored = Some(offset);
+        }
+    }
+}
+
+/// Offset kept as text in a file.
+#[derive(Debug)]
+pub struct FileOffsetStore {
+    path: PathBuf,
+}
+
+impl FileOffsetStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl OffsetStore for FileOffsetStore {
+    async fn load(&self) -> Option<i64> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(offset) => match offset.trim().parse() {
+                Ok(offset) => Some(offset),
+                Err(e) => {
+                    tracing::warn!("Invalid update offset in {}: {}", self.path.display(), e);
+                    None
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", self.path.display(), e);
+                None
+            }
+        }
+    }
+
+    async fn save(&self, offset: i64) {
+        // Written to a temporary file first so a crash never leaves a truncated offset.
+        let tmp = self.path.with_extension("tmp");
+        let result = async {
+            tokio::fs::write(&tmp, offset.to_string()).await?;
+            tokio::fs::rename(&tmp, &self.path).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to save update offset {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_offset_survives_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("offset");
+
+        let store = FileOffsetStore::new(&path);
+        assert_eq!(store.load().await, None);
+        store.save(42).await;
+        store.save(43).await;
+
+        let reopened = FileOffsetStore::new(&path);
+        assert_eq!(reopened.load().await, Some(43));
+        let files: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, ["offset"]);
+    }
+
+    #[tokio::test]
+    async fn invalid_offset_file_loads_as_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("offset");
+        std::fs::write(&path, "not a number").unwrap();
+        assert_eq!(FileOffsetStore::new(&path).load().await, None);
+    }
+}
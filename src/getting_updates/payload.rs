@@ -1,13 +1,12 @@
-use reqwest::multipart::Form;
 use serde::{Deserialize, Serialize};
 
-use crate::utils::ToMultipart;
+use crate::{available_types::InputFile, transport::Form, utils::ToMultipart};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SetWebhookPayload {
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub certificate: Option<String>,
+    pub certificate: Option<InputFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ip_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,10 +33,10 @@ impl ToMultipart for SetWebhookPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             form = form.text("url", self.url);
             if let Some(c) = self.certificate {
-                form = crate::utils::file_to_multipart("certificate".to_string(), c, form).await?;
+                form = c.add_to_form("certificate", form).await?;
             }
             if let Some(ip) = self.ip_address {
                 form = form.text("ip_address", ip);
@@ -0,0 +1,247 @@
+//! Self-signed certificates for webhooks on a bare IP or a domain without a CA-signed one.
+//!
+//! ```no_run
+//! # use telegram_bot_api_rs::{bot::Bot, getting_updates::payload::SetWebhookPayload};
+//! # async fn run(bot: Bot, mut payload: SetWebhookPayload) -> anyhow::Result<()> {
+//! use telegram_bot_api_rs::getting_updates::{
+//!     self_signed::SelfSignedCert, webhook_server::WebhookConfig,
+//! };
+//!
+//! let cert = SelfSignedCert::load_or_generate("203.0.113.7", "webhook.crt", "webhook.key")?;
+//! let config = WebhookConfig {
+//!     tls: Some(cert.tls()?),
+//!     ..Default::default()
+//! };
+//! let server = bot.serve_webhook("0.0.0.0:8443", config).await?;
+//! payload.url = "https://203.0.113.7:8443/".to_string();
+//! payload.certificate = Some(cert.input_file());
+//! bot.set_webhook(payload).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, path::Path};
+
+use anyhow::Context;
+use rcgen::{CertificateParams, DnType, KeyPair};
+use time::{Duration, OffsetDateTime};
+use tokio_rustls::rustls::pki_types::{ServerName, UnixTime};
+use webpki::{anchor_from_trusted_cert, EndEntityCert, KeyUsage, ALL_VERIFICATION_ALGS};
+
+use crate::available_types::InputFile;
+
+use super::webhook_server::WebhookTls;
+
+const VALIDITY_DAYS: i64 = 3650;
+
+/// An ECDSA P-256 certificate whose common name is the webhook's host.
+#[derive(Clone)]
+pub struct SelfSignedCert {
+    cert_pem: String,
+    key_pem: String,
+}
+
+impl SelfSignedCert {
+    /// Generates a key pair and a certificate for `host`, an IP address or a domain,
+    /// valid for ten years.
+    pub fn generate(host: &str) -> anyhow::Result<Self> {
+        let key_pair = KeyPair::generate()?;
+        let mut params = CertificateParams::new(vec![host.to_string()])?;
+        params.distinguished_name.push(DnType::CommonName, host);
+        let now = OffsetDateTime::now_utc();
+        params.not_before = now - Duration::days(1);
+        params.not_after = now + Duration::days(VALIDITY_DAYS);
+        let cert = params.self_signed(&key_pair)?;
+        Ok(Self {
+            cert_pem: cert.pem(),
+            key_pem: key_pair.serialize_pem(),
+        })
+    }
+
+    /// Loads the certificate and key saved by a previous run, or generates them for
+    /// `host` and saves them. A saved certificate that expired, is for another host or
+    /// does not belong to the saved key is replaced.
+    pub fn load_or_generate<C: AsRef<Path>, K: AsRef<Path>>(
+        host: &str,
+        cert_path: C,
+        key_path: K,
+    ) -> anyhow::Result<Self> {
+        let cert_path = cert_path.as_ref();
+        let key_path = key_path.as_ref();
+        if cert_path.exists() && key_path.exists() {
+            let saved = Self {
+                cert_pem: std::fs::read_to_string(cert_path)
+                    .with_context(|| format!("Failed to read {}", cert_path.display()))?,
+                key_pem: std::fs::read_to_string(key_path)
+                    .with_context(|| format!("Failed to read {}", key_path.display()))?,
+            };
+            if saved.is_valid_for(host) {
+                return Ok(saved);
+            }
+            tracing::info!(
+                "Saved webhook certificate {} is expired, not for {} or not for the saved key, generating a new one",
+                cert_path.display(),
+                host
+            );
+        }
+        let cert = Self::generate(host)?;
+        write_private(key_path, &cert.key_pem)
+            .with_context(|| format!("Failed to write {}", key_path.display()))?;
+        std::fs::write(cert_path, &cert.cert_pem)
+            .with_context(|| format!("Failed to write {}", cert_path.display()))?;
+        Ok(cert)
+    }
+
+    /// Whether the certificate is currently valid, its subject alternative name matches
+    /// `host` and it belongs to the private key.
+    pub fn is_valid_for(&self, host: &str) -> bool {
+        let (Ok(name), Ok(key_pair)) =
+            (ServerName::try_from(host), KeyPair::from_pem(&self.key_pem))
+        else {
+            return false;
+        };
+        let Some(Ok(der)) = rustls_pemfile::certs(&mut self.cert_pem.as_bytes()).next() else {
+            return false;
+        };
+        let (Ok(cert), Ok(anchor)) = (
+            EndEntityCert::try_from(&der),
+            anchor_from_trusted_cert(&der),
+        ) else {
+            return false;
+        };
+        cert.verify_is_valid_for_subject_name(&name).is_ok()
+            && cert.subject_public_key_info().as_ref() == key_pair.public_key_der()
+            // Self-signed, so the certificate is its own trust anchor.
+            && cert
+                .verify_for_usage(
+                    ALL_VERIFICATION_ALGS,
+                    &[anchor],
+                    &[],
+                    UnixTime::now(),
+                    KeyUsage::server_auth(),
+                    None,
+                    None,
+                )
+                .is_ok()
+    }
+
+    pub fn cert_pem(&self) -> &str {
+        &self.cert_pem
+    }
+
+    pub fn key_pem(&self) -> &str {
+        &self.key_pem
+    }
+
+    /// TLS config for [`WebhookConfig::tls`](super::webhook_server::WebhookConfig::tls).
+    pub fn tls(&self) -> anyhow::Result<WebhookTls> {
+        WebhookTls::from_pem(self.cert_pem.as_bytes(), self.key_pem.as_bytes())
+    }
+
+    /// The public certificate, for `SetWebhookPayload.certificate`.
+    pub fn input_file(&self) -> InputFile {
+        InputFile::memory("webhook.pem", self.cert_pem.clone())
+    }
+}
+
+impl fmt::Debug for SelfSignedCert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelfSignedCert")
+            .field("cert_pem", &self.cert_pem)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::{io::Write, os::unix::fs::OpenOptionsExt};
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_rustls::rustls::pki_types::CertificateDer;
+
+    use super::*;
+
+    fn der(cert: &SelfSignedCert) -> CertificateDer<'static> {
+        rustls_pemfile::certs(&mut cert.cert_pem().as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn generated_certs_parse_and_verify() {
+        for host in ["203.0.113.7", "bot.example.com"] {
+            let cert = SelfSignedCert::generate(host).unwrap();
+            let der = der(&cert);
+            let serial = EndEntityCert::try_from(&der).unwrap().serial().to_vec();
+            assert_eq!(serial[0] & 0x80, 0, "serial {:02x?} is negative", serial);
+            assert!(cert.is_valid_for(host));
+            assert!(!cert.is_valid_for("198.51.100.1"));
+            cert.tls().unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_expired_cert_and_other_key() {
+        let key_pair = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec!["203.0.113.7".to_string()]).unwrap();
+        let now = OffsetDateTime::now_utc();
+        params.not_before = now - Duration::days(10);
+        params.not_after = now - Duration::days(1);
+        let expired = SelfSignedCert {
+            cert_pem: params.self_signed(&key_pair).unwrap().pem(),
+            key_pem: key_pair.serialize_pem(),
+        };
+        assert!(!expired.is_valid_for("203.0.113.7"));
+
+        let cert = SelfSignedCert::generate("203.0.113.7").unwrap();
+        let other = SelfSignedCert::generate("203.0.113.7").unwrap();
+        let mismatched = SelfSignedCert {
+            cert_pem: cert.cert_pem.clone(),
+            key_pem: other.key_pem.clone(),
+        };
+        assert!(cert.is_valid_for("203.0.113.7"));
+        assert!(!mismatched.is_valid_for("203.0.113.7"));
+    }
+
+    #[test]
+    fn load_or_generate_replaces_cert_for_other_host() {
+        let dir = std::env::temp_dir().join(format!("self_signed_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+
+        let first = SelfSignedCert::load_or_generate("203.0.113.7", &cert_path, &key_path).unwrap();
+        let reused =
+            SelfSignedCert::load_or_generate("203.0.113.7", &cert_path, &key_path).unwrap();
+        assert_eq!(first.cert_pem(), reused.cert_pem());
+
+        let other =
+            SelfSignedCert::load_or_generate("198.51.100.1", &cert_path, &key_path).unwrap();
+        assert_ne!(first.cert_pem(), other.cert_pem());
+        assert!(other.is_valid_for("198.51.100.1"));
+        assert!(!other.is_valid_for("203.0.113.7"));
+
+        // A key that does not belong to the saved certificate is replaced too.
+        std::fs::write(&key_path, first.key_pem()).unwrap();
+        let replaced =
+            SelfSignedCert::load_or_generate("198.51.100.1", &cert_path, &key_path).unwrap();
+        assert_ne!(other.cert_pem(), replaced.cert_pem());
+        assert!(replaced.is_valid_for("198.51.100.1"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
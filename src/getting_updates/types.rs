@@ -1,22 +1,53 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 use crate::{
     available_types::{
-        BusinessConnection, BusinessMessagesDeleted, CallbackQuery, ChatBoostRemoved,
-        ChatBoostUpdated, ChatJoinRequest, ChatMemberUpdated, Message, MessageReactionCountUpdated,
-        MessageReactionUpdated, Poll, PollAnswer,
+        BusinessConnection, BusinessMessagesDeleted, CallbackQuery, Chat, ChatBoostRemoved,
+        ChatBoostSource, ChatBoostUpdated, ChatJoinRequest, ChatMemberUpdated,
+        MaybeInaccessibleMessage, Message, MessageReactionCountUpdated, MessageReactionUpdated,
+        Poll, PollAnswer, User,
     },
     inline_mode::types::{ChosenInlineResult, InlineQuery},
     payments::types::{PreCheckoutQuery, ShippingQuery},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Update {
     pub update_id: i64,
     #[serde(flatten)]
     pub data: UpdateData,
 }
 
+impl<'de> Deserialize<'de> for Update {
+    fn deserialize<D>(deserializer: D) -> Result<Update, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut raw = Value::deserialize(deserializer)?;
+        let update_id = raw
+            .get("update_id")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| D::Error::missing_field("update_id"))?;
+        // A single update this crate cannot decode must not fail the whole batch.
+        let data = match UpdateData::deserialize(&raw) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to decode update {}, passing it on as Unknown: {}",
+                    update_id,
+                    e
+                );
+                if let Value::Object(map) = &mut raw {
+                    map.remove("update_id");
+                }
+                UpdateData::Unknown(raw)
+            }
+        };
+        Ok(Update { update_id, data })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum UpdateData {
@@ -86,9 +117,155 @@ pub enum UpdateData {
     RemovedChatBoost {
         removed_chat_boost: ChatBoostRemoved,
     },
+    /// An update of a kind this crate does not know yet, or one it failed to decode,
+    /// without its `update_id`.
+    #[serde(skip_deserializing)]
+    Unknown(Value),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Update {
+    pub fn kind(&self) -> AllowedUpdateType {
+        self.data.kind()
+    }
+
+    pub fn chat(&self) -> Option<&Chat> {
+        self.data.chat()
+    }
+
+    pub fn from(&self) -> Option<&User> {
+        self.data.from()
+    }
+
+    pub fn message(&self) -> Option<&Message> {
+        self.data.message()
+    }
+}
+
+impl UpdateData {
+    /// The kind of update, usable in `allowed_updates`.
+    pub fn kind(&self) -> AllowedUpdateType {
+        match self {
+            UpdateData::Message { .. } => AllowedUpdateType::Message,
+            UpdateData::EditedMessage { .. } => AllowedUpdateType::EditedMessage,
+            UpdateData::ChannelPost { .. } => AllowedUpdateType::ChannelPost,
+            UpdateData::EditedChannelPost { .. } => AllowedUpdateType::EditedChannelPost,
+            UpdateData::BusinessConnection { .. } => AllowedUpdateType::BusinessConnection,
+            UpdateData::BusinessMessage { .. } => AllowedUpdateType::BusinessMessage,
+            UpdateData::EditedBusinessMessage { .. } => AllowedUpdateType::EditedBusinessMessage,
+            UpdateData::DeletedBusinessMessages { .. } => {
+                AllowedUpdateType::DeletedBusinessMessages
+            }
+            UpdateData::MessageReaction { .. } => AllowedUpdateType::MessageReaction,
+            UpdateData::MessageReactionCount { .. } => AllowedUpdateType::MessageReactionCount,
+            UpdateData::InlineQuery { .. } => AllowedUpdateType::InlineQuery,
+            UpdateData::ChosenInlineResult { .. } => AllowedUpdateType::ChosenInlineResult,
+            UpdateData::CallbackQuery { .. } => AllowedUpdateType::CallbackQuery,
+            UpdateData::ShippingQuery { .. } => AllowedUpdateType::ShippingQuery,
+            UpdateData::PreCheckoutQuery { .. } => AllowedUpdateType::PreCheckoutQuery,
+            UpdateData::Poll { .. } => AllowedUpdateType::Poll,
+            UpdateData::PollAnswer { .. } => AllowedUpdateType::PollAnswer,
+            UpdateData::MyChatMember { .. } => AllowedUpdateType::MyChatMember,
+            UpdateData::ChatMember { .. } => AllowedUpdateType::ChatMember,
+            UpdateData::ChatJoinRequest { .. } => AllowedUpdateType::ChatJoinRequest,
+            UpdateData::ChatBoost { .. } => AllowedUpdateType::ChatBoost,
+            UpdateData::RemovedChatBoost { .. } => AllowedUpdateType::RemovedChatBoost,
+            UpdateData::Unknown(raw) => AllowedUpdateType::Other(
+                raw.as_object()
+                    .and_then(|map| map.keys().next())
+                    .cloned()
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    /// The new, edited or business message this update carries.
+    pub fn message(&self) -> Option<&Message> {
+        match self {
+            UpdateData::Message { message }
+            | UpdateData::EditedMessage {
+                edited_message: message,
+            }
+            | UpdateData::ChannelPost {
+                channel_post: message,
+            }
+            | UpdateData::EditedChannelPost {
+                edited_channel_post: message,
+            }
+            | UpdateData::BusinessMessage {
+                business_message: message,
+            }
+            | UpdateData::EditedBusinessMessage {
+                edited_business_message: message,
+            } => Some(message),
+            _ => None,
+        }
+    }
+
+    /// The chat the update happened in, if it belongs to one.
+    pub fn chat(&self) -> Option<&Chat> {
+        if let Some(message) = self.message() {
+            return Some(&message.chat);
+        }
+        match self {
+            UpdateData::DeletedBusinessMessages {
+                deleted_business_messages,
+            } => Some(&deleted_business_messages.chat),
+            UpdateData::MessageReaction { message_reaction } => Some(&message_reaction.chat),
+            UpdateData::MessageReactionCount {
+                message_reaction_count,
+            } => Some(&message_reaction_count.chat),
+            UpdateData::CallbackQuery { callback_query } => match &callback_query.message {
+                MaybeInaccessibleMessage::Message(message) => Some(&message.chat),
+                MaybeInaccessibleMessage::InaccessibleMessage(message) => Some(&message.chat),
+            },
+            UpdateData::PollAnswer { poll_answer } => poll_answer.voter_chat.as_ref(),
+            UpdateData::MyChatMember { my_chat_member } => Some(&my_chat_member.chat),
+            UpdateData::ChatMember { chat_member } => Some(&chat_member.chat),
+            UpdateData::ChatJoinRequest { chat_join_request } => Some(&chat_join_request.chat),
+            UpdateData::ChatBoost { chat_boost } => Some(&chat_boost.chat),
+            UpdateData::RemovedChatBoost { removed_chat_boost } => Some(&removed_chat_boost.chat),
+            _ => None,
+        }
+    }
+
+    /// The user who caused the update, if known.
+    pub fn from(&self) -> Option<&User> {
+        if let Some(message) = self.message() {
+            return message.from.as_ref();
+        }
+        match self {
+            UpdateData::BusinessConnection {
+                business_connection,
+            } => Some(&business_connection.user),
+            UpdateData::MessageReaction { message_reaction } => message_reaction.user.as_ref(),
+            UpdateData::InlineQuery { inline_query } => Some(&inline_query.from),
+            UpdateData::ChosenInlineResult {
+                chosen_inline_result,
+            } => Some(&chosen_inline_result.from),
+            UpdateData::CallbackQuery { callback_query } => Some(&callback_query.from),
+            UpdateData::ShippingQuery { shipping_query } => Some(&shipping_query.from),
+            UpdateData::PreCheckoutQuery { pre_checkout_query } => Some(&pre_checkout_query.from),
+            UpdateData::PollAnswer { poll_answer } => poll_answer.user.as_ref(),
+            UpdateData::MyChatMember { my_chat_member } => Some(&my_chat_member.from),
+            UpdateData::ChatMember { chat_member } => Some(&chat_member.from),
+            UpdateData::ChatJoinRequest { chat_join_request } => Some(&chat_join_request.from),
+            UpdateData::ChatBoost { chat_boost } => boost_user(&chat_boost.boost.source),
+            UpdateData::RemovedChatBoost { removed_chat_boost } => {
+                boost_user(&removed_chat_boost.source)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn boost_user(source: &ChatBoostSource) -> Option<&User> {
+    match source {
+        ChatBoostSource::Premium { user } | ChatBoostSource::GiftCode { user } => Some(user),
+        ChatBoostSource::Giveaway { user, .. } => user.as_ref(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AllowedUpdateType {
     #[serde(rename = "message")]
     Message,
@@ -134,6 +311,9 @@ pub enum AllowedUpdateType {
     ChatBoost,
     #[serde(rename = "removed_chat_boost")]
     RemovedChatBoost,
+    /// A kind added to the Bot API after this crate, e.g. `purchased_paid_media`.
+    #[serde(untagged)]
+    Other(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
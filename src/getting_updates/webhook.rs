@@ -1,18 +1,27 @@
 use crate::bot::Bot;
 
-use anyhow::Result;
+use crate::error::Result;
+use crate::telegram_method::{json_methods, multipart_methods};
 
 use super::{
     payload::{DeleteWebhookPayload, SetWebhookPayload},
     types::WebhookInfo,
 };
 
+json_methods! {
+    DeleteWebhookPayload => "deleteWebhook": bool,
+}
+
+multipart_methods! {
+    SetWebhookPayload => "setWebhook": bool,
+}
+
 impl Bot {
     pub async fn set_webhook(&self, payload: SetWebhookPayload) -> Result<bool> {
-        self.call_api_multipart("setWebhook", payload).await
+        self.execute(&payload).await
     }
     pub async fn delete_webhook(&self, payload: &DeleteWebhookPayload) -> Result<bool> {
-        self.call_api_json("deleteWebhook", payload).await
+        self.execute(payload).await
     }
     pub async fn get_webhook_info(&self) -> Result<WebhookInfo> {
         self.call_api_no_payload("getWebhookInfo").await
@@ -0,0 +1,315 @@
+//! Receives updates pushed by Telegram to a webhook registered with [`Bot::set_webhook`].
+//!
+//! ```no_run
+//! # async fn run(bot: telegram_bot_api_rs::bot::Bot) -> anyhow::Result<()> {
+//! use telegram_bot_api_rs::getting_updates::webhook_server::{WebhookConfig, WebhookTls};
+//!
+//! let config = WebhookConfig {
+//!     path: "/telegram".to_string(),
+//!     secret_token: Some("s3cret".to_string()),
+//!     tls: Some(WebhookTls::from_pem_files("cert.pem", "key.pem")?),
+//!     ..Default::default()
+//! };
+//! let server = bot.serve_webhook("0.0.0.0:8443", config).await?;
+//! let mut updates = bot.subscribe_updates();
+//! while let Ok(update) = updates.recv().await {
+//!     println!("{:?}", update);
+//! }
+//! server.stop().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, net::SocketAddr, path::Path, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use hyper_util::{rt::TokioIo, service::TowerToHyperService};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, ToSocketAddrs},
+    task::JoinHandle,
+};
+use tokio_rustls::{rustls, TlsAcceptor};
+use tokio_util::sync::CancellationToken;
+
+use crate::bot::Bot;
+
+use super::{payload::SetWebhookPayload, types::Update, UpdateDelivery};
+
+const SECRET_TOKEN_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_BACKOFF_INITIAL: Duration = Duration::from_millis(100);
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Path Telegram posts updates to, e.g. `/telegram`.
+    pub path: String,
+    /// Must match `SetWebhookPayload.secret_token`; requests without it are rejected.
+    pub secret_token: Option<String>,
+    /// Where received updates go. Telegram is answered only after an update was
+    /// accepted and retries it otherwise.
+    pub delivery: UpdateDelivery,
+    /// Serves HTTPS instead of HTTP; only used by [`Bot::serve_webhook`].
+    pub tls: Option<WebhookTls>,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            path: "/".to_string(),
+            secret_token: None,
+            delivery: UpdateDelivery::default(),
+            tls: None,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Takes the path and secret token from the payload passed to [`Bot::set_webhook`].
+    pub fn from_payload(payload: &SetWebhookPayload) -> Self {
+        Self {
+            path: url_path(&payload.url).to_string(),
+            secret_token: payload.secret_token.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+fn url_path(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    match after_scheme.find('/') {
+        Some(i) => &after_scheme[i..],
+        None => "/",
+    }
+}
+
+/// Certificate and private key the webhook server presents to Telegram.
+#[derive(Clone)]
+pub struct WebhookTls(Arc<rustls::ServerConfig>);
+
+impl WebhookTls {
+    /// Loads a PEM certificate chain and private key.
+    pub fn from_pem(cert: &[u8], key: &[u8]) -> anyhow::Result<Self> {
+        let certs = rustls_pemfile::certs(&mut &*cert).collect::<Result<Vec<_>, _>>()?;
+        let key = rustls_pemfile::private_key(&mut &*key)?.context("No private key found")?;
+        let config = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+        Ok(Self(Arc::new(config)))
+    }
+
+    pub fn from_pem_files<C: AsRef<Path>, K: AsRef<Path>>(cert: C, key: K) -> anyhow::Result<Self> {
+        let cert = cert.as_ref();
+        let key = key.as_ref();
+        Self::from_pem(
+            &std::fs::read(cert).with_context(|| format!("Failed to read {}", cert.display()))?,
+            &std::fs::read(key).with_context(|| format!("Failed to read {}", key.display()))?,
+        )
+    }
+}
+
+impl fmt::Debug for WebhookTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WebhookTls")
+    }
+}
+
+struct WebhookState {
+    bot: Bot,
+    secret_token: Option<String>,
+    delivery: UpdateDelivery,
+    cancel: CancellationToken,
+}
+
+impl Bot {
+    /// A router accepting Telegram's webhook requests at `config.path`, to merge into
+    /// an existing axum app. Updates go to the same place as with polling.
+    pub fn webhook_router(&self, config: &WebhookConfig) -> Router {
+        self.webhook_router_with(config, CancellationToken::new())
+    }
+
+    fn webhook_router_with(&self, config: &WebhookConfig, cancel: CancellationToken) -> Router {
+        let state = Arc::new(WebhookState {
+            bot: self.clone(),
+            secret_token: config.secret_token.clone(),
+            delivery: config.delivery.clone(),
+            cancel,
+        });
+        Router::new()
+            .route(&config.path, post(receive_update))
+            .with_state(state)
+    }
+
+    /// Serves the webhook on `addr` in a background task, over HTTPS if `config.tls`
+    /// is set. Telegram only connects to ports 443, 80, 88 and 8443.
+    pub async fn serve_webhook<A: ToSocketAddrs>(
+        &self,
+        addr: A,
+        config: WebhookConfig,
+    ) -> anyhow::Result<WebhookHandle> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let cancel = CancellationToken::new();
+        let app = self.webhook_router_with(&config, cancel.clone());
+        let acceptor = config.tls.map(|tls| TlsAcceptor::from(tls.0));
+        let task = tokio::spawn(accept_connections(listener, app, acceptor, cancel.clone()));
+        Ok(WebhookHandle {
+            cancel,
+            task,
+            local_addr,
+        })
+    }
+}
+
+async fn receive_update(
+    State(state): State<Arc<WebhookState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if let Some(secret_token) = &state.secret_token {
+        let received = headers
+            .get(SECRET_TOKEN_HEADER)
+            .map(|value| value.as_bytes())
+            .unwrap_or_default();
+        if !constant_time_eq(received, secret_token.as_bytes()) {
+            tracing::warn!("Rejected webhook request with a wrong secret token");
+            return StatusCode::UNAUTHORIZED;
+        }
+    }
+    let update: Update = match serde_json::from_slice(&body) {
+        Ok(update) => update,
+        Err(e) => {
+            tracing::warn!("Failed to decode webhook update: {}", e);
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+    match state
+        .bot
+        .deliver(&state.delivery, update, &state.cancel)
+        .await
+    {
+        Ok(true) => StatusCode::OK,
+        Ok(false) => StatusCode::SERVICE_UNAVAILABLE,
+        Err(e) => {
+            tracing::error!("Failed to handle webhook update: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+async fn accept_connections(
+    listener: TcpListener,
+    app: Router,
+    acceptor: Option<TlsAcceptor>,
+    cancel: CancellationToken,
+) {
+    let mut backoff = ACCEPT_BACKOFF_INITIAL;
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => {
+                    backoff = ACCEPT_BACKOFF_INITIAL;
+                    accepted
+                }
+                // E.g. running out of file descriptors; retrying right away would spin.
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to accept webhook connection, retrying in {:?}: {}",
+                        backoff,
+                        e
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff) => {}
+                        _ = cancel.cancelled() => break,
+                    }
+                    backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+                    continue;
+                }
+            },
+            _ = cancel.cancelled() => break,
+        };
+        let app = app.clone();
+        let acceptor = acceptor.clone();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            let result = match acceptor {
+                // A client that never finishes the handshake must not hold the task forever.
+                Some(acceptor) => {
+                    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await
+                    {
+                        Ok(Ok(stream)) => serve_connection(stream, app, cancel).await,
+                        Ok(Err(e)) => Err(e.into()),
+                        Err(_) => Err(anyhow::anyhow!("TLS handshake timed out")),
+                    }
+                }
+                None => serve_connection(stream, app, cancel).await,
+            };
+            if let Err(e) = result {
+                tracing::debug!("Webhook connection from {} failed: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn serve_connection<S>(
+    stream: S,
+    app: Router,
+    cancel: CancellationToken,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let conn = hyper::server::conn::http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), TowerToHyperService::new(app));
+    tokio::pin!(conn);
+    tokio::select! {
+        result = conn.as_mut() => result?,
+        _ = cancel.cancelled() => {
+            conn.as_mut().graceful_shutdown();
+            conn.await?
+        }
+    }
+    Ok(())
+}
+
+/// Controls the server started by [`Bot::serve_webhook`].
+///
+/// Dropping the handle leaves the server running.
+#[derive(Debug)]
+pub struct WebhookHandle {
+    cancel: CancellationToken,
+    task: JoinHandle<()>,
+    local_addr: SocketAddr,
+}
+
+impl WebhookHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections and finishes the requests in flight.
+    pub async fn stop(self) {
+        self.cancel.cancel();
+        let _ = self.task.await;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
@@ -1,15 +1,20 @@
 use crate::bot::Bot;
-use anyhow::Result;
+use crate::error::Result;
+use crate::telegram_method::json_methods;
 use resp::SentWebAppMessage;
 pub mod payload;
 pub mod resp;
 pub mod types;
 
+json_methods! {
+    payload::AnswerWebAppQuery => "answerWebAppQuery": SentWebAppMessage,
+}
+
 impl Bot {
     pub async fn answer_web_app_query(
         &self,
         payload: &payload::AnswerWebAppQuery,
     ) -> Result<SentWebAppMessage> {
-        self.call_api_json("answerWebAppQuery", payload).await
+        self.execute(payload).await
     }
 }
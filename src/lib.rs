@@ -1,11 +1,22 @@
 pub mod available_methods;
 pub mod available_types;
 pub mod bot;
+pub mod download;
+pub mod error;
 pub mod games;
 pub mod getting_updates;
 pub mod inline_mode;
+pub mod middleware;
 pub mod payments;
+pub mod progress;
+pub mod rate_limit;
 pub mod stickers;
+pub mod telegram_method;
 pub mod telegram_passport;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod token;
+pub mod transport;
 pub mod updateing_messages;
+pub mod upload_cache;
 pub mod utils;
@@ -0,0 +1,79 @@
+use std::{fmt, sync::Arc, time::Instant};
+
+use crate::{
+    bot::{Bot, CallApiResp},
+    error::Result,
+    transport::ApiRequest,
+};
+
+/// Hook around every api call made by a `Bot`.
+///
+/// A middleware sees the [`ApiRequest`] before it is sent and may change it,
+/// answers by calling [`Next::run`] and may inspect or replace the response.
+/// Returning without calling `next` short-circuits the call, e.g. for caching
+/// or a dry-run mode. Middlewares run in the order they were added with
+/// [`Bot::with_middleware`](crate::bot::Bot::with_middleware), around the rate limiter,
+/// upload progress tracking and the transport.
+///
+/// Middlewares see the raw [`CallApiResp`] with `result` as json; it is decoded
+/// into the method's response type, and `ok: false` turned into an
+/// [`ApiError`](crate::error::ApiError), only after the chain returns.
+#[async_trait::async_trait]
+pub trait Middleware: fmt::Debug + Send + Sync {
+    async fn handle(&self, request: ApiRequest, next: Next<'_>) -> Result<CallApiResp>;
+}
+
+/// The rest of the middleware chain, ending in the rate limiter and the transport.
+pub struct Next<'a> {
+    bot: &'a Bot,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(bot: &'a Bot) -> Self {
+        Self {
+            bot,
+            middlewares: &bot.middlewares,
+        }
+    }
+
+    pub async fn run(self, request: ApiRequest) -> Result<CallApiResp> {
+        match self.middlewares.split_first() {
+            Some((middleware, rest)) => {
+                let next = Next {
+                    bot: self.bot,
+                    middlewares: rest,
+                };
+                middleware.handle(request, next).await
+            }
+            None => self.bot.transmit(request).await,
+        }
+    }
+}
+
+/// Logs every api call with its duration and outcome through `tracing`.
+#[derive(Debug, Clone, Default)]
+pub struct TracingMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for TracingMiddleware {
+    async fn handle(&self, request: ApiRequest, next: Next<'_>) -> Result<CallApiResp> {
+        let method = request.method.clone();
+        let start = Instant::now();
+        let resp = next.run(request).await;
+        match &resp {
+            Ok(resp) if resp.ok => {
+                tracing::debug!("Called {} in {:?}", method, start.elapsed())
+            }
+            Ok(resp) => tracing::warn!(
+                "Called {} in {:?}, Code: {}, Description: {}",
+                method,
+                start.elapsed(),
+                resp.error_code.unwrap_or(0),
+                resp.description.as_deref().unwrap_or("No description")
+            ),
+            Err(e) => tracing::warn!("Failed to call {}: {}", method, e),
+        }
+        resp
+    }
+}
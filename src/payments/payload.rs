@@ -1,12 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::available_types::{InlineKeyboardMarkup, ReplyParameters};
+use crate::available_types::{ChatId, InlineKeyboardMarkup, ReplyParameters};
 
 use super::types::{LabeledPrice, ShippingOption};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendInvoicePayload {
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
     pub title: String,
@@ -126,6 +126,14 @@ pub struct StarTransaction {
     pub created_at: i64,
 }
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GetStarTransactionsPayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefundStarPaymentPayload {
     pub user_id: i64,
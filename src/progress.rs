@@ -0,0 +1,116 @@
+use std::{
+    fmt,
+    io::Cursor,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::transport::{FileData, FilePart, FileReader, Form, Part};
+
+/// Progress of one file part of a multipart request.
+#[derive(Debug, Clone)]
+pub struct UploadProgress {
+    pub method: String,
+    /// Form field the file is uploaded under, e.g. `video` or an attach name.
+    pub field: String,
+    pub file_name: Option<String>,
+    pub sent: u64,
+    /// `None` for readers of unknown length.
+    pub total: Option<u64>,
+}
+
+/// Callback invoked every time a chunk of a file part has been handed to the transport.
+///
+/// It is called often and from the transport's task, so it should be cheap; forward
+/// to a `tokio::sync::watch` channel to consume the progress elsewhere.
+#[derive(Clone)]
+pub struct ProgressHandler(Arc<dyn Fn(UploadProgress) + Send + Sync>);
+
+impl ProgressHandler {
+    pub fn new<F: Fn(UploadProgress) + Send + Sync + 'static>(callback: F) -> Self {
+        Self(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for ProgressHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
+
+/// Wraps every file part of `form` so that reading it reports progress to `handler`.
+pub(crate) async fn track(
+    form: Form,
+    method: &str,
+    handler: &ProgressHandler,
+) -> anyhow::Result<Form> {
+    let mut tracked = Form::new();
+    for (field, part) in form.into_fields() {
+        let Part::File(file) = part else {
+            tracked = tracked.part(field, part);
+            continue;
+        };
+        let (reader, total): (Box<dyn AsyncRead + Send + Unpin>, _) = match file.data {
+            FileData::Bytes(data) => {
+                let len = data.len() as u64;
+                (Box::new(Cursor::new(data)), Some(len))
+            }
+            FileData::Path(path) => {
+                let file = tokio::fs::File::open(&path).await?;
+                let len = file.metadata().await?.len();
+                (Box::new(file), Some(len))
+            }
+            FileData::Reader { reader, len } => (reader.take()?, len),
+        };
+        let progress = UploadProgress {
+            method: method.to_string(),
+            field: field.clone(),
+            file_name: file.file_name.clone(),
+            sent: 0,
+            total,
+        };
+        let reader = ProgressReader {
+            inner: reader,
+            progress,
+            handler: handler.clone(),
+        };
+        tracked = tracked.part(
+            field,
+            Part::File(FilePart {
+                file_name: file.file_name,
+                mime: file.mime,
+                data: FileData::Reader {
+                    reader: FileReader::new(reader),
+                    len: total,
+                },
+            }),
+        );
+    }
+    Ok(tracked)
+}
+
+struct ProgressReader {
+    inner: Box<dyn AsyncRead + Send + Unpin>,
+    progress: UploadProgress,
+    handler: ProgressHandler,
+}
+
+impl AsyncRead for ProgressReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let before = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        let read = (buf.filled().len() - before) as u64;
+        if read > 0 {
+            self.progress.sent += read;
+            (self.handler.0)(self.progress.clone());
+        }
+        poll
+    }
+}
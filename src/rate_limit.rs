@@ -0,0 +1,155 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use tokio::{sync::Mutex, time::Instant};
+
+/// At most `count` requests in any window of `period`; a `count` of 0 disables the quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub count: u32,
+    pub period: Duration,
+}
+
+impl Quota {
+    pub const fn new(count: u32, period: Duration) -> Self {
+        Self { count, period }
+    }
+}
+
+/// Limits applied to message sending methods, defaulting to the ones documented by Telegram.
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    /// Per private chat (positive numeric `chat_id`).
+    pub per_private_chat: Quota,
+    /// Per group or channel (negative numeric `chat_id` or `@username`).
+    pub per_group: Quota,
+    /// Across all chats.
+    pub global: Quota,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            per_private_chat: Quota::new(1, Duration::from_secs(1)),
+            per_group: Quota::new(20, Duration::from_secs(60)),
+            global: Quota::new(30, Duration::from_secs(1)),
+        }
+    }
+}
+
+/// Client-side throttling of `send*`, `copyMessage*` and `forwardMessage*` calls.
+///
+/// Requests over the limit wait for a free slot instead of failing. Clones share
+/// the same state, so one limiter can be used by every clone of a `Bot`.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    global: VecDeque<Instant>,
+    chats: HashMap<String, VecDeque<Instant>>,
+    prune_at: usize,
+}
+
+const MIN_PRUNE_AT: usize = 1024;
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimits::default())
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            state: Arc::new(Mutex::new(State {
+                prune_at: MIN_PRUNE_AT,
+                ..Default::default()
+            })),
+        }
+    }
+
+    pub fn limits(&self) -> &RateLimits {
+        &self.limits
+    }
+
+    pub(crate) fn applies_to(method: &str) -> bool {
+        (method.starts_with("send") && method != "sendChatAction")
+            || method.starts_with("copyMessage")
+            || method.starts_with("forwardMessage")
+    }
+
+    fn chat_quota(&self, chat_id: &str) -> Quota {
+        match chat_id.parse::<i64>() {
+            Ok(id) if id > 0 => self.limits.per_private_chat,
+            _ => self.limits.per_group,
+        }
+    }
+
+    /// Waits until a request to `chat_id` fits in both the chat and the global quota.
+    pub async fn acquire(&self, chat_id: Option<&str>) {
+        let chat_quota = chat_id.map(|id| self.chat_quota(id));
+        loop {
+            let now = Instant::now();
+            let wait_until = {
+                let mut state = self.state.lock().await;
+                state.prune(now, &self.limits);
+
+                let mut wait_until = free_at(&mut state.global, self.limits.global, now);
+                if let (Some(chat_id), Some(quota)) = (chat_id, chat_quota) {
+                    let window = state.chats.entry(chat_id.to_string()).or_default();
+                    wait_until = wait_until.max(free_at(window, quota, now));
+                }
+                if wait_until <= now {
+                    state.global.push_back(now);
+                    if let Some(chat_id) = chat_id {
+                        if let Some(window) = state.chats.get_mut(chat_id) {
+                            window.push_back(now);
+                        }
+                    }
+                    return;
+                }
+                wait_until
+            };
+            tokio::time::sleep_until(wait_until).await;
+        }
+    }
+}
+
+/// Drops timestamps that left the window and returns when the next slot frees up.
+fn free_at(window: &mut VecDeque<Instant>, quota: Quota, now: Instant) -> Instant {
+    while window
+        .front()
+        .is_some_and(|t| now.duration_since(*t) >= quota.period)
+    {
+        window.pop_front();
+    }
+    if quota.count == 0 || (window.len() as u32) < quota.count {
+        return now;
+    }
+    let index = window.len() - quota.count as usize;
+    window[index] + quota.period
+}
+
+impl State {
+    /// Forgets idle chats once the map has grown, so long broadcasts do not leak memory.
+    fn prune(&mut self, now: Instant, limits: &RateLimits) {
+        if self.chats.len() < self.prune_at {
+            return;
+        }
+        let period = limits.per_private_chat.period.max(limits.per_group.period);
+        self.chats.retain(|_, window| {
+            window
+                .back()
+                .is_some_and(|t| now.duration_since(*t) < period)
+        });
+        self.prune_at = (self.chats.len() * 2).max(MIN_PRUNE_AT);
+    }
+}
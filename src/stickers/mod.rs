@@ -6,102 +6,122 @@ use crate::{
     bot::Bot,
 };
 
-use anyhow::Result;
+use crate::error::Result;
+use crate::telegram_method::{json_methods, multipart_methods};
 use payload::*;
 use types::{Sticker, StickerSet};
 
+json_methods! {
+    GetStickerSetPayload => "getStickerSet": StickerSet,
+    GetCustomEmojiStickersPayload => "getCustomEmojiStickers": Vec<Sticker>,
+    SetStickerPositionInSetPayload => "setStickerPositionInSet": bool,
+    DeleteStickerFromSetPayload => "deleteStickerFromSet": bool,
+    SetStickerEmojiListPayload => "setStickerEmojiList": bool,
+    SetStickerKeywordsPayload => "setStickerKeywords": bool,
+    SetStickerMaskPositionPayload => "setStickerMaskPosition": bool,
+    SetStickerSetTitlePayload => "setStickerSetTitle": bool,
+    SetCustomEmojiStickerSetThumbnailPayload => "setCustomEmojiStickerSetThumbnail": bool,
+    DeleteStickerSetPayload => "deleteStickerSet": bool,
+}
+
+multipart_methods! {
+    SendStickerPayload => "sendSticker": Message,
+    CreateNewStickerSetPayload => "createNewStickerSet": bool,
+    AddStickerToSetPayload => "addStickerToSet": bool,
+    ReplaceStickerInSetPayload => "replaceStickerInSet": bool,
+    SetStickerSetThumbnailPayload => "setStickerSetThumbnail": bool,
+    UploadStickerFilePayload => "uploadStickerFile": File,
+}
+
 impl Bot {
     pub async fn send_sticker(&self, payload: SendStickerPayload) -> Result<Message> {
-        self.call_api_multipart("sendSticker", payload).await
+        self.execute(&payload).await
     }
 
     pub async fn get_sticker_set(&self, payload: &GetStickerSetPayload) -> Result<StickerSet> {
-        self.call_api_json("GetStickerSet", payload).await
+        self.execute(payload).await
     }
 
     pub async fn get_custom_emoji_stickers(
         &self,
         payload: &GetCustomEmojiStickersPayload,
     ) -> Result<Vec<Sticker>> {
-        self.call_api_json("GetCustomEmojiStickers", payload).await
+        self.execute(payload).await
     }
 
-    pub async fn upload_sticker_file(&self, payload: &UploadStickerFilePayload) -> Result<File> {
-        self.call_api_json("UploadStickerFile", payload).await
+    pub async fn upload_sticker_file(&self, payload: UploadStickerFilePayload) -> Result<File> {
+        self.execute(&payload).await
     }
 
     pub async fn create_new_sticker_set(
         &self,
         payload: CreateNewStickerSetPayload,
     ) -> Result<bool> {
-        self.call_api_multipart("CreateNewStickerSet", payload)
-            .await
+        self.execute(&payload).await
     }
 
     pub async fn add_sticker_to_set(&self, payload: AddStickerToSetPayload) -> Result<bool> {
-        self.call_api_multipart("AddStickerToSet", payload).await
+        self.execute(&payload).await
     }
 
     pub async fn set_sticker_position_in_set(
         &self,
         payload: &SetStickerPositionInSetPayload,
     ) -> Result<bool> {
-        self.call_api_json("SetStickerPositionInSet", payload).await
+        self.execute(payload).await
     }
 
     pub async fn delete_sticker_from_set(
         &self,
         payload: &DeleteStickerFromSetPayload,
     ) -> Result<bool> {
-        self.call_api_json("DeleteStickerFromSet", payload).await
+        self.execute(payload).await
     }
 
     pub async fn replace_sticker_in_set(
         &self,
         payload: ReplaceStickerInSetPayload,
     ) -> Result<bool> {
-        self.call_api_multipart("ReplaceStickerInSet", payload)
-            .await
+        self.execute(&payload).await
     }
 
     pub async fn set_sticker_emoji_list(
         &self,
         payload: &SetStickerEmojiListPayload,
     ) -> Result<bool> {
-        self.call_api_json("SetStickerEmojiList", payload).await
+        self.execute(payload).await
     }
 
     pub async fn set_sticker_keywords(&self, payload: &SetStickerKeywordsPayload) -> Result<bool> {
-        self.call_api_json("SetStickerKeywords", payload).await
+        self.execute(payload).await
     }
 
     pub async fn set_sticker_mask_position(
         &self,
         payload: &SetStickerMaskPositionPayload,
     ) -> Result<bool> {
-        self.call_api_json("SetStickerMaskPosition", payload).await
+        self.execute(payload).await
     }
 
     pub async fn set_sticker_set_title(&self, payload: &SetStickerSetTitlePayload) -> Result<bool> {
-        self.call_api_json("SetStickerSetTitle", payload).await
+        self.execute(payload).await
     }
 
     pub async fn set_sticker_set_thumbnail(
         &self,
-        payload: &SetStickerSetThumbnailPayload,
+        payload: SetStickerSetThumbnailPayload,
     ) -> Result<bool> {
-        self.call_api_json("SetStickerSetThumbnail", payload).await
+        self.execute(&payload).await
     }
 
     pub async fn set_custom_emoji_sticker_set_thumbnail(
         &self,
         payload: &SetCustomEmojiStickerSetThumbnailPayload,
     ) -> Result<bool> {
-        self.call_api_json("SetCustomEmojiStickerSetThumbnail", payload)
-            .await
+        self.execute(payload).await
     }
 
     pub async fn delete_sticker_set(&self, payload: &DeleteStickerSetPayload) -> Result<bool> {
-        self.call_api_json("DeleteStickerSet", payload).await
+        self.execute(payload).await
     }
 }
@@ -1,11 +1,9 @@
-use std::path::Path;
-
-use reqwest::multipart::Form;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    available_types::{InlineKeyboardMarkup, ReplyParameters},
-    utils::{file_to_multipart, ToMultipart},
+    available_types::{ChatId, InlineKeyboardMarkup, InputFile, ReplyParameters},
+    transport::Form,
+    utils::ToMultipart,
 };
 
 use super::types::{InputSticker, MaskPosition};
@@ -14,10 +12,10 @@ use super::types::{InputSticker, MaskPosition};
 pub struct SendStickerPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_thread_id: Option<i64>,
-    pub sticker: String,
+    pub sticker: InputFile,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub emoji: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -46,14 +44,9 @@ impl ToMultipart for SendStickerPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
-            let path = Path::new(&self.sticker);
-            if path.is_file() {
-                form = file_to_multipart("sticker".to_string(), path, form).await?;
-            }else {
-                form = form.text("sticker", self.sticker);
-            }
-            form = form.text("chat_id", self.chat_id);
+            let mut form = Form::new();
+            form = self.sticker.add_to_form("sticker", form).await?;
+            form = form.text("chat_id", self.chat_id.to_string());
             if let Some(emoji) = self.emoji {
                 form = form.text("emoji", emoji);
             }
@@ -103,10 +96,38 @@ pub enum StickerFormat {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UploadStickerFilePayload {
     pub user_id: i64,
-    pub sticker: String,
+    pub sticker: InputFile,
     pub sticker_format: StickerFormat,
 }
 
+impl ToMultipart for UploadStickerFilePayload {
+    fn to_multipart<'async_trait>(
+        self,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = anyhow::Result<Form>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let mut form = Form::new();
+            form = form.text("user_id", self.user_id.to_string());
+            form = self.sticker.add_to_form("sticker", form).await?;
+            form = form.text(
+                "sticker_format",
+                serde_json::to_value(&self.sticker_format)?
+                    .as_str()
+                    .unwrap_or_default(),
+            );
+            Ok(form)
+        })
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CreateNewStickerSetPayload {
     pub user_id: i64,
@@ -120,7 +141,7 @@ pub struct CreateNewStickerSetPayload {
 }
 
 impl ToMultipart for CreateNewStickerSetPayload {
-     fn to_multipart<'async_trait>(
+    fn to_multipart<'async_trait>(
         mut self,
     ) -> ::core::pin::Pin<
         Box<
@@ -133,7 +154,7 @@ impl ToMultipart for CreateNewStickerSetPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
+            let mut form = Form::new();
             for sticker in self.stickers.iter_mut() {
                 form = sticker.try_part(form).await?;
             }
@@ -162,7 +183,7 @@ pub struct AddStickerToSetPayload {
 
 impl ToMultipart for AddStickerToSetPayload {
     fn to_multipart<'async_trait>(
-        self,
+        mut self,
     ) -> ::core::pin::Pin<
         Box<
             dyn ::core::future::Future<Output = anyhow::Result<Form>>
@@ -174,7 +195,8 @@ impl ToMultipart for AddStickerToSetPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = self.sticker.to_multipart().await?;
+            let mut form = self.sticker.try_part(Form::new()).await?;
+            form = form.text("sticker", serde_json::to_string(&self.sticker)?);
             form = form.text("user_id", self.user_id.to_string());
             form = form.text("name", self.name);
             Ok(form)
@@ -203,7 +225,7 @@ pub struct ReplaceStickerInSetPayload {
 
 impl ToMultipart for ReplaceStickerInSetPayload {
     fn to_multipart<'async_trait>(
-        self,
+        mut self,
     ) -> ::core::pin::Pin<
         Box<
             dyn ::core::future::Future<Output = anyhow::Result<Form>>
@@ -215,7 +237,8 @@ impl ToMultipart for ReplaceStickerInSetPayload {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            let mut form = self.sticker.to_multipart().await?;
+            let mut form = self.sticker.try_part(Form::new()).await?;
+            form = form.text("sticker", serde_json::to_string(&self.sticker)?);
             form = form.text("user_id", self.user_id.to_string());
             form = form.text("name", self.name);
             form = form.text("old_sticker", self.old_sticker);
@@ -253,10 +276,37 @@ pub struct SetStickerSetTitlePayload {
 pub struct SetStickerSetThumbnailPayload {
     pub name: String,
     pub user_id: i64,
-    pub thumbnail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<InputFile>,
     pub format: String,
 }
 
+impl ToMultipart for SetStickerSetThumbnailPayload {
+    fn to_multipart<'async_trait>(
+        self,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = anyhow::Result<Form>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let mut form = Form::new();
+            form = form.text("name", self.name);
+            form = form.text("user_id", self.user_id.to_string());
+            if let Some(thumbnail) = self.thumbnail {
+                form = thumbnail.add_to_form("thumbnail", form).await?;
+            }
+            form = form.text("format", self.format);
+            Ok(form)
+        })
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SetCustomEmojiStickerSetThumbnailPayload {
     pub name: String,
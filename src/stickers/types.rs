@@ -1,13 +1,9 @@
-use std::path::Path;
-
-use anyhow::{Ok, Result};
-use rand::Rng;
-use reqwest::multipart::Form;
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    available_types::{File, PhotoSize},
-    utils::{file_to_multipart, ToMultipart},
+    available_types::{File, InputFile, PhotoSize},
+    transport::Form,
 };
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -57,7 +53,7 @@ pub struct StickerSet {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct InputSticker {
-    pub sticker: String,
+    pub sticker: InputFile,
     pub format: String,
     pub emoji_list: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -67,59 +63,6 @@ pub struct InputSticker {
 
 impl InputSticker {
     pub async fn try_part(&mut self, form: Form) -> Result<Form> {
-        let path = Path::new(&self.sticker);
-        if !path.is_file() {
-            return Ok(form);
-        }
-        let name = path
-            .file_name()
-            .and_then(|f| f.to_str())
-            .and_then(|f| Some(f.to_string()))
-            .unwrap_or_else(|| {
-                // 生成一个16位的随机字符串
-                let mut rng = rand::thread_rng();
-                let mut name = String::with_capacity(16);
-                for _ in 0..16 {
-                    name.push(rng.gen_range(b'a'..b'z') as char);
-                }
-                name
-            });
-
-        let form = file_to_multipart(name.clone(), self.sticker.clone(), form).await?;
-        self.sticker = format!("attach://{}", name);
-
-        Ok(form)
-    }
-}
-
-impl ToMultipart for InputSticker {
-    fn to_multipart<'async_trait>(
-        self,
-    ) -> ::core::pin::Pin<
-        Box<
-            dyn ::core::future::Future<Output = anyhow::Result<Form>>
-                + ::core::marker::Send
-                + 'async_trait,
-        >,
-    >
-    where
-        Self: 'async_trait,
-    {
-        Box::pin(async move {
-            let mut form = reqwest::multipart::Form::new();
-            let path = Path::new(&self.sticker);
-            if path.is_file() {
-                form = file_to_multipart("sticker".to_string(), self.sticker, form).await?;
-            } else {
-                form = form.text("sticker", self.sticker);
-            }
-            form = form.text("format", self.format);
-            form = form.text("emoji_list", serde_json::to_string(&self.emoji_list)?);
-            if let Some(mp) = self.mask_position {
-                form = form.text("mask_position", serde_json::to_string(&mp)?);
-            }
-            form = form.text("keywords", serde_json::to_string(&self.keywords)?);
-            Ok(form)
-        })
+        self.sticker.attach(form).await
     }
 }
@@ -0,0 +1,48 @@
+use serde::de::DeserializeOwned;
+
+use crate::transport::RequestBody;
+
+/// Ties a payload type to its Bot API method name, response type and encoding.
+///
+/// Every payload that belongs to exactly one method implements this trait, so
+/// it can be sent with [`Bot::execute`](crate::bot::Bot::execute).
+#[async_trait::async_trait]
+pub trait TelegramMethod: Send + Sync {
+    type Response: DeserializeOwned;
+    const NAME: &'static str;
+
+    /// Encodes the payload as json or as a multipart form.
+    async fn to_body(&self) -> anyhow::Result<RequestBody>;
+}
+
+macro_rules! json_methods {
+    ($($payload:ty => $name:literal: $response:ty,)*) => {$(
+        #[async_trait::async_trait]
+        impl $crate::telegram_method::TelegramMethod for $payload {
+            type Response = $response;
+            const NAME: &'static str = $name;
+
+            async fn to_body(&self) -> anyhow::Result<$crate::transport::RequestBody> {
+                Ok($crate::transport::RequestBody::Json(serde_json::to_value(self)?))
+            }
+        }
+    )*};
+}
+
+macro_rules! multipart_methods {
+    ($($payload:ty => $name:literal: $response:ty,)*) => {$(
+        #[async_trait::async_trait]
+        impl $crate::telegram_method::TelegramMethod for $payload {
+            type Response = $response;
+            const NAME: &'static str = $name;
+
+            async fn to_body(&self) -> anyhow::Result<$crate::transport::RequestBody> {
+                let form = $crate::utils::ToMultipart::to_multipart(self.clone()).await?;
+                Ok($crate::transport::RequestBody::Multipart(form))
+            }
+        }
+    )*};
+}
+
+pub(crate) use json_methods;
+pub(crate) use multipart_methods;
@@ -2,13 +2,16 @@ use crate::bot::Bot;
 
 pub mod payload;
 pub mod types;
-use anyhow::Result;
-use types::PassportElementError;
+use crate::error::Result;
+use crate::telegram_method::json_methods;
+use payload::SetPassportDataErrors;
+
+json_methods! {
+    SetPassportDataErrors => "setPassportDataErrors": bool,
+}
+
 impl Bot {
-    pub async fn set_passport_data_errors(
-        &self,
-        payload: &Vec<PassportElementError>,
-    ) -> Result<bool> {
-        self.call_api_json("setPassportDataErrors", payload).await
+    pub async fn set_passport_data_errors(&self, payload: &SetPassportDataErrors) -> Result<bool> {
+        self.execute(payload).await
     }
 }
@@ -0,0 +1,725 @@
+//! A local fake of the Telegram Bot API for integration tests.
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use telegram_bot_api_rs::testing::FakeServer;
+//!
+//! let server = FakeServer::start().await?;
+//! let bot = server.bot();
+//! let me = bot.get_me().await?;
+//! assert_eq!(me.id, server.bot_user().id);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, FromRequest, Multipart, Request, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Router,
+};
+use serde_json::{json, Map, Value};
+use tokio::sync::{oneshot, Notify};
+
+use crate::{
+    available_types::User,
+    bot::{Bot, CallApiResp},
+    getting_updates::types::{Update, UpdateData},
+};
+
+const FAKE_TOKEN: &str = "123456:fake-token";
+
+/// A request received by the [`FakeServer`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Json body, query string or multipart text fields.
+    pub params: Map<String, Value>,
+    pub files: Vec<RecordedFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedFile {
+    /// Multipart field name the file was uploaded under.
+    pub field: String,
+    pub file_name: Option<String>,
+    pub mime: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// Returns a parameter as text, whether it was sent as a json string or number.
+    pub fn param(&self, key: &str) -> Option<String> {
+        match self.params.get(key)? {
+            Value::String(s) => Some(s.clone()),
+            Value::Null => None,
+            v => Some(v.to_string()),
+        }
+    }
+
+    /// Returns a parameter holding json, decoding it if it was sent as multipart text.
+    pub fn json_param(&self, key: &str) -> Option<Value> {
+        match self.params.get(key)? {
+            Value::String(s) => serde_json::from_str(s).ok(),
+            v => Some(v.clone()),
+        }
+    }
+
+    pub fn file(&self, field: &str) -> Option<&RecordedFile> {
+        self.files.iter().find(|f| f.field == field)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StoredFile {
+    file_id: String,
+    file_unique_id: String,
+    file_path: String,
+    file_name: Option<String>,
+    mime: Option<String>,
+    data: Vec<u8>,
+}
+
+impl StoredFile {
+    fn to_file(&self) -> Value {
+        json!({
+            "file_id": self.file_id,
+            "file_unique_id": self.file_unique_id,
+            "file_size": self.data.len(),
+            "file_path": self.file_path,
+        })
+    }
+
+    /// Superset of the fields of `Document`, `Video`, `Audio`, `Sticker` and friends.
+    fn to_media(&self) -> Value {
+        json!({
+            "file_id": self.file_id,
+            "file_unique_id": self.file_unique_id,
+            "file_size": self.data.len(),
+            "file_name": self.file_name,
+            "mime_type": self.mime,
+            "width": 0,
+            "height": 0,
+            "duration": 0,
+            "length": 0,
+            "is_animated": false,
+            "is_video": false,
+            "type": "regular",
+        })
+    }
+}
+
+#[derive(Debug)]
+struct FakeState {
+    token: String,
+    bot_user: User,
+    next_id: AtomicI64,
+    updates: Mutex<VecDeque<(i64, Value)>>,
+    updates_notify: Notify,
+    requests: Mutex<Vec<RecordedRequest>>,
+    requests_notify: Notify,
+    files: Mutex<HashMap<String, StoredFile>>,
+    responses: Mutex<HashMap<String, VecDeque<CallApiResp>>>,
+    webhook_url: Mutex<String>,
+}
+
+/// Local http server speaking the Bot API wire format.
+///
+/// Covers `getMe`, `getUpdates`, `sendMessage`, media sends, `editMessage*`,
+/// `getFile`, file downloads and the webhook methods. Other methods answer
+/// `404` unless a response was queued with [`FakeServer::enqueue_response`].
+/// The server shuts down when dropped.
+#[derive(Debug)]
+pub struct FakeServer {
+    addr: SocketAddr,
+    state: Arc<FakeState>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl FakeServer {
+    pub async fn start() -> Result<Self> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(FakeState {
+            token: FAKE_TOKEN.to_string(),
+            bot_user: User {
+                id: 123456,
+                is_bot: true,
+                first_name: "Fake Bot".to_string(),
+                username: Some("fake_bot".to_string()),
+                ..Default::default()
+            },
+            next_id: AtomicI64::new(1),
+            updates: Mutex::new(VecDeque::new()),
+            updates_notify: Notify::new(),
+            requests: Mutex::new(Vec::new()),
+            requests_notify: Notify::new(),
+            files: Mutex::new(HashMap::new()),
+            responses: Mutex::new(HashMap::new()),
+            webhook_url: Mutex::new(String::new()),
+        });
+        // Uploads may be far larger than axum's default 2 MB body limit.
+        let app = Router::new()
+            .fallback(handle)
+            .layer(DefaultBodyLimit::disable())
+            .with_state(state.clone());
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+                let _ = shutdown_rx.await;
+            });
+            if let Err(e) = server.await {
+                tracing::error!("Fake Bot API server failed: {}", e);
+            }
+        });
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn token(&self) -> &str {
+        &self.state.token
+    }
+
+    pub fn bot_user(&self) -> &User {
+        &self.state.bot_user
+    }
+
+    /// A `Bot` pointed at this server.
+    pub fn bot(&self) -> Bot {
+        Bot::new(self.token()).with_api_url(self.url())
+    }
+
+    /// Queues an update for `getUpdates` and returns the `update_id` assigned to it.
+    pub fn push_update(&self, data: UpdateData) -> i64 {
+        let update_id = self.state.next_id();
+        let update = Update { update_id, data };
+        let value = serde_json::to_value(&update).expect("Update is always serializable");
+        self.push_value(update_id, value);
+        update_id
+    }
+
+    /// Queues a raw update object, e.g. one with a kind this crate does not know yet.
+    pub fn push_raw_update(&self, mut update: Value) -> i64 {
+        let update_id = self.state.next_id();
+        if let Value::Object(map) = &mut update {
+            map.insert("update_id".to_string(), update_id.into());
+        }
+        self.push_value(update_id, update);
+        update_id
+    }
+
+    fn push_value(&self, update_id: i64, value: Value) {
+        self.state
+            .updates
+            .lock()
+            .unwrap()
+            .push_back((update_id, value));
+        self.state.updates_notify.notify_waiters();
+    }
+
+    /// Updates that were not confirmed by a `getUpdates` offset yet.
+    pub fn pending_updates(&self) -> usize {
+        self.state.updates.lock().unwrap().len()
+    }
+
+    /// Answers the next call of `method` with `response` instead of the built-in behaviour.
+    pub fn enqueue_response<S: Into<String>>(&self, method: S, response: CallApiResp) {
+        self.state
+            .responses
+            .lock()
+            .unwrap()
+            .entry(method.into())
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Answers the next call of `method` with an api error.
+    pub fn enqueue_error<S: Into<String>, D: Into<String>>(
+        &self,
+        method: S,
+        error_code: i64,
+        description: D,
+    ) {
+        self.enqueue_response(
+            method,
+            CallApiResp {
+                ok: false,
+                result: None,
+                error_code: Some(error_code),
+                description: Some(description.into()),
+                parameters: None,
+            },
+        );
+    }
+
+    /// Stores a file that can be fetched with `getFile` and downloaded.
+    pub fn add_file<N: Into<String>>(&self, file_name: N, data: Vec<u8>) -> String {
+        self.state
+            .store_file(Some(file_name.into()), None, data)
+            .file_id
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    pub fn requests_for(&self, method: &str) -> Vec<RecordedRequest> {
+        self.state
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Requests of every method starting with `send`, plus `copyMessage`/`forwardMessage`.
+    pub fn sent_messages(&self) -> Vec<RecordedRequest> {
+        self.state
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| {
+                r.method.starts_with("send")
+                    || r.method.starts_with("copyMessage")
+                    || r.method.starts_with("forwardMessage")
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear_requests(&self) {
+        self.state.requests.lock().unwrap().clear();
+    }
+
+    /// Waits until `count` calls of `method` have been received.
+    pub async fn wait_for_requests(
+        &self,
+        method: &str,
+        count: usize,
+        timeout: Duration,
+    ) -> Result<Vec<RecordedRequest>> {
+        let wait = async {
+            loop {
+                let notified = self.state.requests_notify.notified();
+                let requests = self.requests_for(method);
+                if requests.len() >= count {
+                    return requests;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out waiting for {} `{}` requests", count, method))
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+impl FakeState {
+    fn next_id(&self) -> i64 {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    fn store_file(
+        &self,
+        file_name: Option<String>,
+        mime: Option<String>,
+        data: Vec<u8>,
+    ) -> StoredFile {
+        let id = self.next_id();
+        let extension = file_name
+            .as_deref()
+            .and_then(|n| n.rsplit_once('.'))
+            .map(|(_, ext)| format!(".{}", ext))
+            .unwrap_or_default();
+        let file = StoredFile {
+            file_id: format!("fake-file-{}", id),
+            file_unique_id: format!("fake-unique-{}", id),
+            file_path: format!("files/file_{}{}", id, extension),
+            file_name,
+            mime,
+            data,
+        };
+        self.files
+            .lock()
+            .unwrap()
+            .insert(file.file_id.clone(), file.clone());
+        file
+    }
+
+    /// Resolves a media field to a stored file: an uploaded part, a known file_id,
+    /// or a placeholder for urls and unknown ids.
+    fn resolve_file(&self, request: &RecordedRequest, value: &str, field: &str) -> StoredFile {
+        let upload = match value.strip_prefix("attach://") {
+            Some(name) => request.file(name),
+            None => request.file(field),
+        };
+        if let Some(upload) = upload {
+            return self.store_file(
+                upload.file_name.clone(),
+                upload.mime.clone(),
+                upload.data.clone(),
+            );
+        }
+        if let Some(file) = self.files.lock().unwrap().get(value) {
+            return file.clone();
+        }
+        self.store_file(None, None, Vec::new())
+    }
+
+    fn message(&self, request: &RecordedRequest, extra: Value) -> Value {
+        let chat_id = request
+            .param("chat_id")
+            .and_then(|id| id.parse::<i64>().ok())
+            .unwrap_or(-1);
+        let mut message = json!({
+            "message_id": request
+                .param("message_id")
+                .and_then(|id| id.parse::<i64>().ok())
+                .unwrap_or_else(|| self.next_id()),
+            "date": SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(1),
+            "chat": {
+                "id": chat_id,
+                "type": if chat_id > 0 { "private" } else { "supergroup" },
+            },
+            "from": self.bot_user,
+        });
+        if let (Value::Object(message), Value::Object(extra)) = (&mut message, extra) {
+            message.extend(extra);
+        }
+        message
+    }
+
+    fn media_message(&self, request: &RecordedRequest, field: &str) -> Value {
+        let value = request.param(field).unwrap_or_default();
+        let file = self.resolve_file(request, &value, field);
+        let media = if field == "photo" {
+            json!([file.to_media()])
+        } else {
+            file.to_media()
+        };
+        let mut extra = Map::new();
+        extra.insert(field.to_string(), media);
+        if let Some(caption) = request.param("caption") {
+            extra.insert("caption".to_string(), caption.into());
+        }
+        self.message(request, Value::Object(extra))
+    }
+
+    fn input_media_message(&self, request: &RecordedRequest, media: &Value) -> Value {
+        let kind = media["type"].as_str().unwrap_or("document");
+        let value = media["media"].as_str().unwrap_or_default();
+        let file = self.resolve_file(request, value, kind);
+        let media_value = if kind == "photo" {
+            json!([file.to_media()])
+        } else {
+            file.to_media()
+        };
+        let mut extra = Map::new();
+        extra.insert(kind.to_string(), media_value);
+        if let Some(caption) = media["caption"].as_str() {
+            extra.insert("caption".to_string(), caption.into());
+        }
+        self.message(request, Value::Object(extra))
+    }
+
+    async fn get_updates(&self, request: &RecordedRequest) -> Value {
+        let offset = request.param("offset").and_then(|o| o.parse::<i64>().ok());
+        let limit = request
+            .param("limit")
+            .and_then(|l| l.parse::<usize>().ok())
+            .filter(|l| *l > 0)
+            .unwrap_or(100);
+        let timeout = request
+            .param("timeout")
+            .and_then(|t| t.parse::<u64>().ok())
+            .unwrap_or(0);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout);
+        loop {
+            let notified = self.updates_notify.notified();
+            {
+                let mut updates = self.updates.lock().unwrap();
+                match offset {
+                    Some(offset) if offset < 0 => {
+                        let keep = offset.unsigned_abs() as usize;
+                        while updates.len() > keep {
+                            updates.pop_front();
+                        }
+                    }
+                    Some(offset) => updates.retain(|(id, _)| *id >= offset),
+                    None => {}
+                }
+                if !updates.is_empty() {
+                    return Value::Array(
+                        updates.iter().take(limit).map(|(_, u)| u.clone()).collect(),
+                    );
+                }
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return Value::Array(Vec::new());
+            }
+        }
+    }
+
+    async fn call(&self, request: &RecordedRequest) -> std::result::Result<Value, (i64, String)> {
+        let method = request.method.as_str();
+        match method {
+            "getMe" => Ok(json!(self.bot_user)),
+            "getUpdates" => {
+                if !self.webhook_url.lock().unwrap().is_empty() {
+                    return Err((
+                        409,
+                        "Conflict: can't use getUpdates method while webhook is active; use deleteWebhook to delete the webhook first".to_string(),
+                    ));
+                }
+                Ok(self.get_updates(request).await)
+            }
+            "sendMessage" => Ok(self.message(
+                request,
+                json!({ "text": request.param("text").unwrap_or_default() }),
+            )),
+            "forwardMessage" => Ok(self.message(request, json!({}))),
+            "copyMessage" => Ok(json!({ "message_id": self.next_id() })),
+            "sendPhoto" => Ok(self.media_message(request, "photo")),
+            "sendAudio" => Ok(self.media_message(request, "audio")),
+            "sendDocument" => Ok(self.media_message(request, "document")),
+            "sendVideo" => Ok(self.media_message(request, "video")),
+            "sendAnimation" => Ok(self.media_message(request, "animation")),
+            "sendVoice" => Ok(self.media_message(request, "voice")),
+            "sendVideoNote" => Ok(self.media_message(request, "video_note")),
+            "sendSticker" => Ok(self.media_message(request, "sticker")),
+            "sendMediaGroup" => {
+                let media = request.json_param("media").unwrap_or_default();
+                let media = media.as_array().cloned().unwrap_or_default();
+                Ok(Value::Array(
+                    media
+                        .iter()
+                        .map(|m| self.input_media_message(request, m))
+                        .collect(),
+                ))
+            }
+            "editMessageText"
+            | "editMessageCaption"
+            | "editMessageMedia"
+            | "editMessageReplyMarkup"
+            | "editMessageLiveLocation"
+            | "stopMessageLiveLocation" => {
+                if request.param("inline_message_id").is_some() {
+                    return Ok(json!(true));
+                }
+                let extra = match method {
+                    "editMessageText" => json!({ "text": request.param("text") }),
+                    "editMessageCaption" => json!({ "caption": request.param("caption") }),
+                    _ => json!({}),
+                };
+                match request.json_param("media") {
+                    Some(media) if method == "editMessageMedia" => {
+                        Ok(self.input_media_message(request, &media))
+                    }
+                    _ => Ok(self.message(request, extra)),
+                }
+            }
+            "getFile" => {
+                let file_id = request.param("file_id").unwrap_or_default();
+                match self.files.lock().unwrap().get(&file_id) {
+                    Some(file) => Ok(file.to_file()),
+                    None => Err((400, "Bad Request: invalid file_id".to_string())),
+                }
+            }
+            "setWebhook" => {
+                *self.webhook_url.lock().unwrap() = request.param("url").unwrap_or_default();
+                Ok(json!(true))
+            }
+            "deleteWebhook" => {
+                self.webhook_url.lock().unwrap().clear();
+                if request.param("drop_pending_updates").as_deref() == Some("true") {
+                    self.updates.lock().unwrap().clear();
+                }
+                Ok(json!(true))
+            }
+            "getWebhookInfo" => Ok(json!({
+                "url": *self.webhook_url.lock().unwrap(),
+                "has_custom_certificate": false,
+                "pending_update_count": self.updates.lock().unwrap().len(),
+            })),
+            "sendChatAction"
+            | "deleteMessage"
+            | "deleteMessages"
+            | "answerCallbackQuery"
+            | "setMyCommands"
+            | "deleteMyCommands"
+            | "logOut"
+            | "close" => Ok(json!(true)),
+            _ => Err((404, "Not Found: method not found".to_string())),
+        }
+    }
+}
+
+async fn read_request(method: String, request: Request) -> Result<RecordedRequest> {
+    let mut params = Map::new();
+    let mut files = Vec::new();
+    if let Some(query) = request.uri().query() {
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            params.insert(key.into_owned(), Value::String(value.into_owned()));
+        }
+    }
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|c| c.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if content_type.starts_with("multipart/form-data") {
+        let mut multipart = Multipart::from_request(request, &()).await?;
+        while let Some(field) = multipart.next_field().await? {
+            let name = field.name().unwrap_or_default().to_string();
+            let file_name = field.file_name().map(|f| f.to_string());
+            let mime = field.content_type().map(|m| m.to_string());
+            let data = field.bytes().await?;
+            match file_name {
+                Some(file_name) => files.push(RecordedFile {
+                    field: name,
+                    file_name: Some(file_name),
+                    mime,
+                    data: data.to_vec(),
+                }),
+                None => {
+                    let text = String::from_utf8_lossy(&data).into_owned();
+                    params.insert(name, Value::String(text));
+                }
+            }
+        }
+    } else {
+        let body = Bytes::from_request(request, &()).await?;
+        if content_type.starts_with("application/json") && !body.is_empty() {
+            if let Value::Object(map) = serde_json::from_slice(&body)? {
+                params.extend(map);
+            }
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            for (key, value) in url::form_urlencoded::parse(&body) {
+                params.insert(key.into_owned(), Value::String(value.into_owned()));
+            }
+        }
+    }
+    Ok(RecordedRequest {
+        method,
+        params,
+        files,
+    })
+}
+
+fn api_response(resp: CallApiResp) -> Response {
+    let status = match resp.ok {
+        true => StatusCode::OK,
+        false => resp
+            .error_code
+            .and_then(|code| StatusCode::from_u16(code as u16).ok())
+            .unwrap_or(StatusCode::BAD_REQUEST),
+    };
+    (status, axum::Json(resp)).into_response()
+}
+
+fn api_error(error_code: i64, description: String) -> Response {
+    api_response(CallApiResp {
+        ok: false,
+        result: None,
+        error_code: Some(error_code),
+        description: Some(description),
+        parameters: None,
+    })
+}
+
+/// The fake serves bots in the test environment (`/bot<token>/test/<method>`) too.
+fn strip_test_env(path: &str) -> &str {
+    path.strip_prefix("test/").unwrap_or(path)
+}
+
+async fn handle(State(state): State<Arc<FakeState>>, request: Request) -> Response {
+    let path = request.uri().path().to_string();
+
+    if let Some(rest) = path.strip_prefix("/file/bot") {
+        let Some((token, file_path)) = rest.split_once('/') else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        if urlencoding::decode(token).ok().as_deref() != Some(state.token.as_str()) {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+        let file_path = strip_test_env(file_path);
+        let files = state.files.lock().unwrap();
+        return match files.values().find(|f| f.file_path == file_path) {
+            Some(file) => file.data.clone().into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        };
+    }
+
+    let Some((token, method)) = path
+        .strip_prefix("/bot")
+        .and_then(|rest| rest.split_once('/'))
+    else {
+        return api_error(404, "Not Found".to_string());
+    };
+    if urlencoding::decode(token).ok().as_deref() != Some(state.token.as_str()) {
+        return api_error(401, "Unauthorized".to_string());
+    }
+    let method = strip_test_env(method);
+
+    let request = match read_request(method.to_string(), request).await {
+        Ok(request) => request,
+        Err(e) => return api_error(400, format!("Bad Request: {}", e)),
+    };
+    state.requests.lock().unwrap().push(request.clone());
+    state.requests_notify.notify_waiters();
+
+    let queued = state
+        .responses
+        .lock()
+        .unwrap()
+        .get_mut(&request.method)
+        .and_then(|queue| queue.pop_front());
+    if let Some(resp) = queued {
+        return api_response(resp);
+    }
+
+    match state.call(&request).await {
+        Ok(result) => api_response(CallApiResp {
+            ok: true,
+            result: Some(result),
+            error_code: None,
+            description: None,
+            parameters: None,
+        }),
+        Err((error_code, description)) => api_error(error_code, description),
+    }
+}
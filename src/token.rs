@@ -0,0 +1,135 @@
+use std::{fmt, path::Path};
+
+use anyhow::Context;
+
+/// Bot token whose `Debug` and `Display` only show the bot id, e.g. `123456:***`.
+///
+/// Use [`Token::expose`] where the real value is needed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Token(String);
+
+impl Token {
+    pub fn new<S: Into<String>>(token: S) -> Self {
+        Self(token.into())
+    }
+
+    /// Reads the token from the environment variable `key`.
+    pub fn from_env(key: &str) -> anyhow::Result<Self> {
+        let token = std::env::var(key).with_context(|| format!("Failed to read {}", key))?;
+        Ok(Self::new(token.trim()))
+    }
+
+    /// Reads the token from a file, ignoring surrounding whitespace.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let token = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read token from {}", path.display()))?;
+        Ok(Self::new(token.trim()))
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Token({})", self)
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&mask(&self.0))
+    }
+}
+
+impl From<String> for Token {
+    fn from(token: String) -> Self {
+        Self(token)
+    }
+}
+
+impl From<&str> for Token {
+    fn from(token: &str) -> Self {
+        Self(token.to_string())
+    }
+}
+
+fn mask(token: &str) -> String {
+    match token.split_once(':') {
+        Some((id, _)) => format!("{}:***", id),
+        None => "***".to_string(),
+    }
+}
+
+/// Masks the token in a Bot API url (`.../bot<token>/...` or `.../file/bot<token>/...`).
+pub(crate) fn redact_url(url: &str) -> String {
+    for (i, _) in url.match_indices("/bot") {
+        let start = i + "/bot".len();
+        let end = url[start..].find('/').map_or(url.len(), |i| start + i);
+        let token = urlencoding::decode(&url[start..end]).unwrap_or_default();
+        if token.contains(':') {
+            return format!("{}{}{}", &url[..start], mask(&token), &url[end..]);
+        }
+    }
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::bot::FileLocation;
+
+    const TOKEN: &str = "123456:AAE-secret_part";
+
+    #[test]
+    fn token_debug_and_display_hide_the_secret() {
+        let token = Token::new(TOKEN);
+        assert_eq!(token.to_string(), "123456:***");
+        assert_eq!(format!("{:?}", token), "Token(123456:***)");
+        assert_eq!(Token::new("no-colon").to_string(), "***");
+        assert_eq!(token.expose(), TOKEN);
+    }
+
+    #[test]
+    fn redacts_api_and_file_urls() {
+        assert_eq!(
+            redact_url(&format!("https://api.telegram.org/bot{}/getMe", TOKEN)),
+            "https://api.telegram.org/bot123456:***/getMe"
+        );
+        assert_eq!(
+            redact_url(&format!(
+                "https://api.telegram.org/file/bot{}/photos/file_1.jpg",
+                TOKEN
+            )),
+            "https://api.telegram.org/file/bot123456:***/photos/file_1.jpg"
+        );
+        assert_eq!(
+            redact_url("https://api.telegram.org/bot123456%3AAAE-secret_part/test/getMe"),
+            "https://api.telegram.org/bot123456:***/test/getMe"
+        );
+        // Paths that merely start with "bot" are left alone.
+        assert_eq!(
+            redact_url("http://localhost/bottles/1"),
+            "http://localhost/bottles/1"
+        );
+    }
+
+    #[test]
+    fn file_location_debug_hides_the_token() {
+        let url = FileLocation::Url(format!(
+            "https://api.telegram.org/file/bot{}/documents/a.txt",
+            TOKEN
+        ));
+        let debug = format!("{:?}", url);
+        assert!(!debug.contains("secret_part"), "{}", debug);
+        assert!(debug.contains("bot123456:***/documents/a.txt"), "{}", debug);
+        assert_eq!(
+            format!("{:?}", FileLocation::Local(PathBuf::from("/var/a.txt"))),
+            "Local(\"/var/a.txt\")"
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, StreamExt};
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    bot::CallApiResp,
+    error::{ApiError, Result},
+    token::redact_url,
+};
+
+/// A single Bot API call as handed to a [`Transport`].
+pub struct ApiRequest {
+    /// Bot API method name, e.g. `sendMessage`.
+    pub method: String,
+    /// Full url of the method, including the bot token.
+    pub url: String,
+    pub body: RequestBody,
+}
+
+impl fmt::Debug for ApiRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiRequest")
+            .field("method", &self.method)
+            .field("url", &redact_url(&self.url))
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub enum RequestBody {
+    Empty,
+    Json(Value),
+    Multipart(Form),
+}
+
+impl RequestBody {
+    /// The `chat_id` parameter of the request, if any.
+    pub fn chat_id(&self) -> Option<String> {
+        match self {
+            RequestBody::Empty => None,
+            RequestBody::Json(value) => match value.get("chat_id")? {
+                Value::String(chat_id) => Some(chat_id.clone()),
+                Value::Number(chat_id) => Some(chat_id.to_string()),
+                _ => None,
+            },
+            RequestBody::Multipart(form) => form.get_text("chat_id").map(|c| c.to_string()),
+        }
+    }
+}
+
+/// Multipart form built by the payload encoders.
+///
+/// Unlike `reqwest::multipart::Form` its fields can be inspected, which lets a
+/// [`Transport`] other than reqwest see exactly what would be uploaded.
+#[derive(Debug, Default, Clone)]
+pub struct Form {
+    fields: Vec<(String, Part)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Part {
+    Text(String),
+    File(FilePart),
+}
+
+#[derive(Debug, Clone)]
+pub struct FilePart {
+    pub file_name: Option<String>,
+    pub mime: Option<String>,
+    pub data: FileData,
+}
+
+/// Content of a [`FilePart`]; files and readers are streamed when the request is sent.
+#[derive(Clone)]
+pub enum FileData {
+    Bytes(Vec<u8>),
+    Path(PathBuf),
+    Reader {
+        reader: FileReader,
+        /// Sent as the part length when known, otherwise the part is chunked.
+        len: Option<u64>,
+    },
+}
+
+impl fmt::Debug for FileData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileData::Bytes(data) => f.debug_struct("Bytes").field("len", &data.len()).finish(),
+            FileData::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            FileData::Reader { len, .. } => f
+                .debug_struct("Reader")
+                .field("len", len)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl FileData {
+    /// Reads the whole content into memory, for transports that cannot stream.
+    pub async fn into_bytes(self) -> anyhow::Result<Vec<u8>> {
+        match self {
+            FileData::Bytes(data) => Ok(data),
+            FileData::Path(path) => Ok(tokio::fs::read(path).await?),
+            FileData::Reader { reader, .. } => {
+                let mut data = Vec::new();
+                reader.take()?.read_to_end(&mut data).await?;
+                Ok(data)
+            }
+        }
+    }
+}
+
+/// Async reader shared between clones, taken by the first request that sends it.
+#[derive(Clone)]
+pub struct FileReader(Arc<Mutex<Option<Box<dyn AsyncRead + Send + Unpin>>>>);
+
+impl FileReader {
+    pub fn new<R: AsyncRead + Send + Unpin + 'static>(reader: R) -> Self {
+        Self(Arc::new(Mutex::new(Some(Box::new(reader)))))
+    }
+
+    /// Fails if the reader was already consumed, e.g. by an earlier attempt of a retried request.
+    pub fn take(&self) -> anyhow::Result<Box<dyn AsyncRead + Send + Unpin>> {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|mut reader| reader.take())
+            .ok_or_else(|| anyhow::anyhow!("The reader was already consumed"))
+    }
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.fields.push((key.into(), Part::Text(value.into())));
+        self
+    }
+
+    pub fn part<K: Into<String>>(mut self, key: K, part: Part) -> Self {
+        self.fields.push((key.into(), part));
+        self
+    }
+
+    pub fn fields(&self) -> &[(String, Part)] {
+        &self.fields
+    }
+
+    pub fn into_fields(self) -> Vec<(String, Part)> {
+        self.fields
+    }
+
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find_map(|(k, part)| match part {
+            Part::Text(text) if k == key => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn get_file(&self, key: &str) -> Option<&FilePart> {
+        self.fields.iter().find_map(|(k, part)| match part {
+            Part::File(file) if k == key => Some(file),
+            _ => None,
+        })
+    }
+}
+
+/// Sends [`ApiRequest`]s to a Bot API server.
+///
+/// [`ReqwestTransport`] is used by default; tests can plug in their own
+/// implementation with [`Bot::with_transport`](crate::bot::Bot::with_transport)
+/// to record requests and return canned responses.
+#[async_trait::async_trait]
+pub trait Transport: fmt::Debug + Send + Sync {
+    async fn send(&self, request: ApiRequest) -> Result<CallApiResp>;
+
+    /// Streams the content behind a file url built by [`Bot::file_url`](crate::bot::Bot::file_url).
+    async fn download(&self, url: &str) -> Result<ByteStream> {
+        let _ = url;
+        Err(ApiError::Payload(anyhow::anyhow!(
+            "{:?} does not support downloading files",
+            self
+        )))
+    }
+}
+
+pub type ByteStream = BoxStream<'static, Result<Bytes>>;
+
+/// Request timeout used unless another one is set.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    pub client: reqwest::Client,
+    /// Per-request timeout, [`DEFAULT_TIMEOUT`] by default. Long polling `getUpdates`
+    /// calls get their own `timeout` parameter added on top, so they are not cut
+    /// short. Multipart uploads are exempt, as their duration depends on the file size.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new(reqwest::Client::default())
+    }
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            timeout: Some(DEFAULT_TIMEOUT),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn request_timeout(&self, request: &ApiRequest) -> Option<Duration> {
+        let timeout = self.timeout?;
+        let poll_timeout = match &request.body {
+            RequestBody::Json(value) if request.method == "getUpdates" => {
+                value.get("timeout").and_then(Value::as_u64).unwrap_or(0)
+            }
+            RequestBody::Multipart(_) => return None,
+            _ => 0,
+        };
+        Some(timeout + Duration::from_secs(poll_timeout))
+    }
+
+    async fn to_reqwest_form(form: Form) -> Result<reqwest::multipart::Form> {
+        let mut multipart = reqwest::multipart::Form::new();
+        for (key, part) in form.fields {
+            multipart = match part {
+                Part::Text(text) => multipart.text(key, text),
+                Part::File(file) => {
+                    let mut file_part = Self::to_reqwest_part(file.data).await?;
+                    if let Some(file_name) = file.file_name {
+                        file_part = file_part.file_name(file_name);
+                    }
+                    if let Some(mime) = file.mime {
+                        file_part = file_part.mime_str(&mime)?;
+                    }
+                    multipart.part(key, file_part)
+                }
+            };
+        }
+        Ok(multipart)
+    }
+
+    async fn to_reqwest_part(data: FileData) -> Result<reqwest::multipart::Part> {
+        use reqwest::{multipart::Part, Body};
+        let part = match data {
+            FileData::Bytes(data) => Part::bytes(data),
+            FileData::Path(path) => {
+                let file = tokio::fs::File::open(&path)
+                    .await
+                    .map_err(anyhow::Error::from)?;
+                let len = file.metadata().await.map_err(anyhow::Error::from)?.len();
+                Part::stream_with_length(Body::wrap_stream(ReaderStream::new(file)), len)
+            }
+            FileData::Reader { reader, len } => {
+                let body = Body::wrap_stream(ReaderStream::new(reader.take()?));
+                match len {
+                    Some(len) => Part::stream_with_length(body, len),
+                    None => Part::stream(body),
+                }
+            }
+        };
+        Ok(part)
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: ApiRequest) -> Result<CallApiResp> {
+        let timeout = self.request_timeout(&request);
+        let mut builder = match request.body {
+            RequestBody::Empty => self.client.get(&request.url),
+            RequestBody::Json(value) => self.client.post(&request.url).json(&value),
+            RequestBody::Multipart(form) => self
+                .client
+                .post(&request.url)
+                .multipart(Self::to_reqwest_form(form).await?),
+        };
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        let bytes = builder.send().await?.bytes().await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    async fn download(&self, url: &str) -> Result<ByteStream> {
+        let resp = self.client.get(url).send().await?.error_for_status()?;
+        Ok(resp
+            .bytes_stream()
+            .map(|chunk| chunk.map_err(ApiError::from))
+            .boxed())
+    }
+}
@@ -1,13 +1,19 @@
 use serde::{Deserialize, Serialize};
 
-use crate::available_types::{InlineKeyboardMarkup, InputMedia, LinkPreviewOptions, MessageEntity};
+use crate::{
+    available_types::{
+        ChatId, InlineKeyboardMarkup, InputMedia, LinkPreviewOptions, MessageEntity,
+    },
+    transport::Form,
+    utils::ToMultipart,
+};
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EditMessageTextPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<String>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -28,7 +34,7 @@ pub struct EditMessageCaptionPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<String>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -49,7 +55,7 @@ pub struct EditMessageMediaPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<String>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -59,12 +65,48 @@ pub struct EditMessageMediaPayload {
     pub reply_markup: Option<InlineKeyboardMarkup>,
 }
 
+impl ToMultipart for EditMessageMediaPayload {
+    fn to_multipart<'async_trait>(
+        mut self,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = anyhow::Result<Form>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let mut form = self.media.try_part(Form::new()).await?;
+            form = form.text("media", serde_json::to_string(&self.media)?);
+            if let Some(business_connection_id) = self.business_connection_id {
+                form = form.text("business_connection_id", business_connection_id);
+            }
+            if let Some(chat_id) = self.chat_id {
+                form = form.text("chat_id", chat_id.to_string());
+            }
+            if let Some(message_id) = self.message_id {
+                form = form.text("message_id", message_id.to_string());
+            }
+            if let Some(inline_message_id) = self.inline_message_id {
+                form = form.text("inline_message_id", inline_message_id);
+            }
+            if let Some(reply_markup) = &self.reply_markup {
+                form = form.text("reply_markup", serde_json::to_string(reply_markup)?);
+            }
+            Ok(form)
+        })
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EditMessageLiveLocationPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<String>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -88,7 +130,7 @@ pub struct StopMessageLiveLocationPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<String>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -102,7 +144,7 @@ pub struct EditMessageReplyMarkupPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub chat_id: Option<String>,
+    pub chat_id: Option<ChatId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -115,7 +157,7 @@ pub struct EditMessageReplyMarkupPayload {
 pub struct StopPollPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub message_id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reply_markup: Option<InlineKeyboardMarkup>,
@@ -125,7 +167,7 @@ pub struct StopPollPayload {
 pub struct DeleteMessagePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub message_id: i64,
 }
 
@@ -133,6 +175,6 @@ pub struct DeleteMessagePayload {
 pub struct DeleteMessagesPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub business_connection_id: Option<String>,
-    pub chat_id: String,
+    pub chat_id: ChatId,
     pub message_ids: Vec<i64>,
 }
@@ -0,0 +1,187 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use futures_util::StreamExt;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio_util::io::ReaderStream;
+
+use crate::transport::{FileData, Form};
+
+/// Remembers the `file_id` Telegram assigned to uploaded content, so sending the
+/// same bytes again reuses it instead of uploading them.
+///
+/// Keys combine the bot id, the kind of file and the SHA-256 of the content.
+/// Only the main file of `sendPhoto`, `sendAudio`, `sendDocument`, `sendVideo`,
+/// `sendAnimation`, `sendVoice`, `sendVideoNote` and `sendSticker` is cached;
+/// readers are always uploaded.
+#[async_trait::async_trait]
+pub trait UploadCache: fmt::Debug + Send + Sync {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn put(&self, key: &str, file_id: String);
+    async fn remove(&self, key: &str);
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryUploadCache {
+    file_ids: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryUploadCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl UploadCache for MemoryUploadCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        self.file_ids.lock().ok()?.get(key).cloned()
+    }
+
+    async fn put(&self, key: &str, file_id: String) {
+        if let Ok(mut file_ids) = self.file_ids.lock() {
+            file_ids.insert(key.to_string(), file_id);
+        }
+    }
+
+    async fn remove(&self, key: &str) {
+        if let Ok(mut file_ids) = self.file_ids.lock() {
+            file_ids.remove(key);
+        }
+    }
+}
+
+/// Upload cache kept in a json file, so it survives restarts.
+#[derive(Debug)]
+pub struct FileUploadCache {
+    path: PathBuf,
+    file_ids: tokio::sync::Mutex<HashMap<String, String>>,
+}
+
+impl FileUploadCache {
+    /// Loads the cache from `path`, starting empty if the file does not exist yet.
+    pub async fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file_ids = match tokio::fs::read(&path).await {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            file_ids: tokio::sync::Mutex::new(file_ids),
+        })
+    }
+
+    async fn save(&self, file_ids: &HashMap<String, String>) {
+        // Written to a temporary file first so a crash never leaves a truncated cache.
+        let tmp = self.path.with_extension("tmp");
+        let result = async {
+            tokio::fs::write(&tmp, serde_json::to_vec(file_ids)?).await?;
+            tokio::fs::rename(&tmp, &self.path).await?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to save upload cache {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl UploadCache for FileUploadCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        self.file_ids.lock().await.get(key).cloned()
+    }
+
+    async fn put(&self, key: &str, file_id: String) {
+        let mut file_ids = self.file_ids.lock().await;
+        if file_ids.get(key) != Some(&file_id) {
+            file_ids.insert(key.to_string(), file_id);
+            self.save(&file_ids).await;
+        }
+    }
+
+    async fn remove(&self, key: &str) {
+        let mut file_ids = self.file_ids.lock().await;
+        if file_ids.remove(key).is_some() {
+            self.save(&file_ids).await;
+        }
+    }
+}
+
+/// The form field holding the file sent by `method`, which is also the field of the
+/// returned `Message` describing it.
+pub(crate) fn cached_field(method: &str) -> Option<&'static str> {
+    match method {
+        "sendPhoto" => Some("photo"),
+        "sendAudio" => Some("audio"),
+        "sendDocument" => Some("document"),
+        "sendVideo" => Some("video"),
+        "sendAnimation" => Some("animation"),
+        "sendVoice" => Some("voice"),
+        "sendVideoNote" => Some("video_note"),
+        "sendSticker" => Some("sticker"),
+        _ => None,
+    }
+}
+
+/// Cache key of the file uploaded under `field`, or `None` if it is not uploaded or is a reader.
+pub(crate) async fn cache_key(
+    bot_id: &str,
+    field: &str,
+    form: &Form,
+) -> anyhow::Result<Option<String>> {
+    let mut hasher = Sha256::new();
+    match form.get_file(field).map(|file| &file.data) {
+        Some(FileData::Bytes(data)) => hasher.update(data),
+        // Streamed in chunks, like the upload itself, instead of read into memory.
+        Some(FileData::Path(path)) => {
+            let mut chunks = ReaderStream::new(tokio::fs::File::open(path).await?);
+            while let Some(chunk) = chunks.next().await {
+                hasher.update(chunk?);
+            }
+        }
+        Some(FileData::Reader { .. }) | None => return Ok(None),
+    }
+    Ok(Some(format!(
+        "{}:{}:{:x}",
+        bot_id,
+        field,
+        hasher.finalize()
+    )))
+}
+
+/// Whether a 400 description says the sent `file_id` is invalid or expired, e.g.
+/// "wrong file identifier/HTTP URL specified" or "FILE_REFERENCE_EXPIRED".
+pub(crate) fn is_file_id_error(description: &str) -> bool {
+    let description = description.to_lowercase().replace('_', " ");
+    ["file identifier", "file id", "file reference"]
+        .iter()
+        .any(|pattern| description.contains(pattern))
+}
+
+/// Replaces the file uploaded under `field` with a cached `file_id`.
+pub(crate) fn with_file_id(form: &Form, field: &str, file_id: &str) -> Form {
+    form.fields()
+        .iter()
+        .fold(Form::new(), |new, (key, part)| match key == field {
+            true => new.text(key.as_str(), file_id),
+            false => new.part(key.as_str(), part.clone()),
+        })
+}
+
+/// The `file_id` of the file sent under `field`, read from the returned `Message`.
+pub(crate) fn sent_file_id(field: &str, message: &Value) -> Option<String> {
+    let file = match &message[field] {
+        // Photos come in several sizes, all with the same content; take the largest.
+        Value::Array(sizes) => sizes.last()?,
+        file => file,
+    };
+    file["file_id"].as_str().map(|file_id| file_id.to_string())
+}
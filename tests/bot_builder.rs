@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use telegram_bot_api_rs::{bot::Bot, error::ApiError};
+
+#[test]
+fn zero_channel_capacity_is_rejected() {
+    let error = Bot::builder("1:a").channel_capacity(0).build().unwrap_err();
+    assert!(matches!(
+        error,
+        ApiError::InvalidConfig("channel_capacity must be greater than 0")
+    ));
+}
+
+#[test]
+fn client_cannot_be_combined_with_client_options() {
+    let error = Bot::builder("1:a")
+        .client(reqwest::Client::new())
+        .connect_timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, ApiError::InvalidConfig(_)));
+
+    Bot::builder("1:a")
+        .client(reqwest::Client::new())
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+}
@@ -0,0 +1,437 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde_json::json;
+use telegram_bot_api_rs::{
+    available_methods::payload::{SendMessagePayload, SendPhotoPayload},
+    available_types::{ChatId, InputFile, ResponseParameters},
+    bot::{CallApiResp, RetryPolicy},
+    error::ApiError,
+    getting_updates::{
+        offset_store::{MemoryOffsetStore, OffsetStore},
+        payload::{DeleteWebhookPayload, SetWebhookPayload},
+        types::{AllowedUpdateType, Update, UpdateData},
+        GetUpdateConfig, PollingStatus, UpdateDelivery,
+    },
+    testing::FakeServer,
+};
+use tokio::sync::mpsc;
+
+fn text_message(text: &str) -> serde_json::Value {
+    json!({
+        "message": {
+            "message_id": 1,
+            "date": 1,
+            "chat": { "id": 42, "type": "private" },
+            "text": text,
+        }
+    })
+}
+
+#[tokio::test]
+async fn polling_delivers_queued_updates() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot();
+    let ids: Vec<i64> = ["a", "b", "c"]
+        .into_iter()
+        .map(|text| server.push_raw_update(text_message(text)))
+        .collect();
+
+    let mut updates = bot.subscribe_updates();
+    let handle = bot.start_get_updates(GetUpdateConfig {
+        timeout: 1,
+        ..Default::default()
+    });
+    for id in &ids {
+        let update = tokio::time::timeout(Duration::from_secs(5), updates.recv()).await??;
+        assert_eq!(update.update_id, *id);
+        assert_eq!(update.chat().map(|chat| chat.id), Some(42));
+    }
+
+    assert_eq!(handle.stop().await, Some(ids[2] + 1));
+    assert_eq!(server.pending_updates(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn send_photo_uploads_multipart() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot();
+    let data = vec![7u8; 64 * 1024];
+
+    let message = bot
+        .send_photo(SendPhotoPayload {
+            chat_id: ChatId::Id(42),
+            photo: InputFile::memory("cat.png", data.clone()),
+            caption: Some("a cat".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(message.chat.id, 42);
+
+    let requests = server.requests_for("sendPhoto");
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(request.param("chat_id").as_deref(), Some("42"));
+    assert_eq!(request.param("caption").as_deref(), Some("a cat"));
+    let photo = request.file("photo").expect("photo was not uploaded");
+    assert_eq!(photo.file_name.as_deref(), Some("cat.png"));
+    assert_eq!(photo.data, data);
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_webhook_uploads_certificate() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot();
+    let certificate = b"-----BEGIN CERTIFICATE-----\n...".to_vec();
+
+    assert!(
+        bot.set_webhook(SetWebhookPayload {
+            url: "https://example.com/hook".to_string(),
+            certificate: Some(InputFile::memory("cert.pem", certificate.clone())),
+            ip_address: None,
+            max_connections: Some(10),
+            allowed_updates: None,
+            drop_pending_updates: None,
+            secret_token: Some("s3cret".to_string()),
+        })
+        .await?
+    );
+    let request = &server.requests_for("setWebhook")[0];
+    assert_eq!(
+        request.param("url").as_deref(),
+        Some("https://example.com/hook")
+    );
+    assert_eq!(request.param("secret_token").as_deref(), Some("s3cret"));
+    assert_eq!(request.param("max_connections").as_deref(), Some("10"));
+    assert_eq!(request.file("certificate").unwrap().data, certificate);
+    assert_eq!(
+        bot.get_webhook_info().await?.url,
+        "https://example.com/hook"
+    );
+
+    // Polling is rejected while a webhook is set.
+    assert_eq!(
+        bot.call_raw("getUpdates", json!({}))
+            .await
+            .unwrap_err()
+            .error_code(),
+        Some(409)
+    );
+
+    bot.delete_webhook(&DeleteWebhookPayload {
+        drop_pending_updates: None,
+    })
+    .await?;
+    assert_eq!(bot.get_webhook_info().await?.url, "");
+    Ok(())
+}
+
+#[tokio::test]
+async fn serves_test_environment() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot().with_test_environment(true);
+    assert_eq!(bot.get_me().await?.id, server.bot_user().id);
+
+    let file_id = server.add_file("notes.txt", b"hello".to_vec());
+    let mut downloaded = Vec::new();
+    bot.download_file_by_id(&file_id, &mut downloaded).await?;
+    assert_eq!(downloaded, b"hello");
+    Ok(())
+}
+
+#[tokio::test]
+async fn restart_does_not_skip_backlog_again() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot();
+    let first = server.push_raw_update(text_message("first"));
+    let second = server.push_raw_update(text_message("second"));
+
+    // The first update fills the channel, so the second one is still pending on restart.
+    let (sender, mut first_rx) = mpsc::channel(1);
+    let handle = bot.start_get_updates(GetUpdateConfig {
+        timeout: 1,
+        delivery: UpdateDelivery::Channel(sender.clone()),
+        ..Default::default()
+    });
+    while sender.capacity() > 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let (sender, mut rx) = mpsc::channel(1);
+    let handle = handle
+        .restart(GetUpdateConfig {
+            timeout: 1,
+            delivery: UpdateDelivery::Channel(sender),
+            skip_backlog: true,
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(first_rx.recv().await.map(|u| u.update_id), Some(first));
+    let update = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await?;
+    assert_eq!(update.map(|u| u.update_id), Some(second));
+    handle.stop().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn panicking_handler_fails_polling_without_committing() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot();
+    let first = server.push_raw_update(text_message("first"));
+    let second = server.push_raw_update(text_message("panic"));
+
+    let handle = bot.start_get_updates(GetUpdateConfig {
+        timeout: 1,
+        delivery: UpdateDelivery::handler(|update: Update| async move {
+            if update.message().and_then(|m| m.text.as_deref()) == Some("panic") {
+                panic!("boom");
+            }
+        }),
+        ..Default::default()
+    });
+    let mut status = handle.status();
+    let failed = tokio::time::timeout(
+        Duration::from_secs(5),
+        status.wait_for(|s| matches!(s, PollingStatus::Failed(_))),
+    )
+    .await??
+    .clone();
+    match failed {
+        PollingStatus::Failed(e) => {
+            assert!(matches!(&*e, ApiError::HandlerPanicked(m) if m == "boom"))
+        }
+        _ => unreachable!(),
+    }
+    assert!(handle.is_finished());
+    assert_eq!(handle.stop().await, Some(first + 1));
+    assert_eq!(server.pending_updates(), 1);
+
+    let (sender, mut rx) = mpsc::channel(1);
+    let handle = bot.start_get_updates(GetUpdateConfig {
+        timeout: 1,
+        offset: Some(first + 1),
+        delivery: UpdateDelivery::Channel(sender),
+        ..Default::default()
+    });
+    let update = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await?;
+    assert_eq!(update.map(|u| u.update_id), Some(second));
+    handle.stop().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_updates_do_not_break_the_batch() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot();
+    let first = server.push_raw_update(text_message("first"));
+    let unknown = server.push_raw_update(json!({
+        "purchased_paid_media": {
+            "from": { "id": 7, "is_bot": false, "first_name": "Ann" },
+            "paid_media_payload": "payload",
+        }
+    }));
+    // A known kind with a missing required field is passed on as unknown too.
+    let broken = server.push_raw_update(json!({ "message": { "message_id": 3 } }));
+    let last = server.push_raw_update(text_message("last"));
+
+    let (sender, mut rx) = mpsc::channel(8);
+    let handle = bot.start_get_updates(GetUpdateConfig {
+        timeout: 1,
+        delivery: UpdateDelivery::Channel(sender),
+        ..Default::default()
+    });
+    let mut received = Vec::new();
+    for _ in 0..4 {
+        received.push(
+            tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await?
+                .expect("polling stopped"),
+        );
+    }
+    handle.stop().await;
+
+    let ids: Vec<_> = received.iter().map(|u| u.update_id).collect();
+    assert_eq!(ids, [first, unknown, broken, last]);
+    assert!(matches!(received[1].data, UpdateData::Unknown(_)));
+    assert_eq!(
+        received[1].kind(),
+        AllowedUpdateType::Other("purchased_paid_media".to_string())
+    );
+    assert!(matches!(received[2].data, UpdateData::Unknown(_)));
+    assert_eq!(
+        received[3].message().and_then(|m| m.text.as_deref()),
+        Some("last")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn offset_is_committed_only_after_delivery() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot();
+    let ids: Vec<i64> = ["a", "b", "c"]
+        .into_iter()
+        .map(|text| server.push_raw_update(text_message(text)))
+        .collect();
+
+    let store = Arc::new(MemoryOffsetStore::new());
+    // The stored offset each update saw while it was being handled.
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (done, mut done_rx) = mpsc::channel(8);
+    let handle = bot.start_get_updates(GetUpdateConfig {
+        timeout: 1,
+        offset_store: Some(store.clone()),
+        delivery: UpdateDelivery::handler({
+            let (store, seen) = (store.clone(), seen.clone());
+            move |update: Update| {
+                let (store, seen, done) = (store.clone(), seen.clone(), done.clone());
+                async move {
+                    let stored = store.load().await;
+                    seen.lock().unwrap().push((update.update_id, stored));
+                    let _ = done.send(()).await;
+                }
+            }
+        }),
+        ..Default::default()
+    });
+    for _ in &ids {
+        tokio::time::timeout(Duration::from_secs(5), done_rx.recv()).await?;
+    }
+    handle.stop().await;
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [
+            (ids[0], None),
+            (ids[1], Some(ids[0] + 1)),
+            (ids[2], Some(ids[1] + 1)),
+        ]
+    );
+    assert_eq!(store.load().await, Some(ids[2] + 1));
+    Ok(())
+}
+
+fn flood_control(retry_after: i64) -> CallApiResp {
+    CallApiResp {
+        ok: false,
+        result: None,
+        error_code: Some(429),
+        description: Some(format!("Too Many Requests: retry after {}", retry_after)),
+        parameters: Some(ResponseParameters {
+            retry_after: Some(retry_after),
+            ..Default::default()
+        }),
+    }
+}
+
+fn hello() -> SendMessagePayload {
+    SendMessagePayload {
+        chat_id: ChatId::Id(42),
+        text: "hello".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn retries_after_flood_control() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot().with_retry_policy(RetryPolicy::default());
+    server.enqueue_response("sendMessage", flood_control(0));
+
+    let message = bot.send_message(&hello()).await?;
+    assert_eq!(message.text.as_deref(), Some("hello"));
+    assert_eq!(server.requests_for("sendMessage").len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn server_errors_are_retried_up_to_max_attempts() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot().with_retry_policy(RetryPolicy {
+        max_attempts: 3,
+        server_error_delay: Some(Duration::from_millis(10)),
+        ..Default::default()
+    });
+    for _ in 0..4 {
+        server.enqueue_error("sendMessage", 502, "Bad Gateway");
+    }
+
+    let error = bot.send_message(&hello()).await.unwrap_err();
+    assert_eq!(error.error_code(), Some(502));
+    assert_eq!(server.requests_for("sendMessage").len(), 3);
+
+    // Without a retry policy the error is returned at once.
+    server.clear_requests();
+    let error = server.bot().send_message(&hello()).await.unwrap_err();
+    assert_eq!(error.error_code(), Some(502));
+    assert_eq!(server.requests_for("sendMessage").len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn reader_uploads_are_not_resent() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot().with_retry_policy(RetryPolicy::default());
+    server.enqueue_response("sendPhoto", flood_control(0));
+
+    let error = bot
+        .send_photo(SendPhotoPayload {
+            chat_id: ChatId::Id(42),
+            photo: InputFile::reader("cat.png", &b"not really a png"[..]),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ApiError::Payload(_)), "{:?}", error);
+    assert!(
+        error.to_string().contains("reader was already consumed"),
+        "{}",
+        error
+    );
+    assert_eq!(server.requests_for("sendPhoto").len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn reports_upload_progress() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let bot = server.bot().with_upload_progress({
+        let progress = progress.clone();
+        move |p| progress.lock().unwrap().push((p.field, p.sent, p.total))
+    });
+    let data: Vec<u8> = (0..300_000u32).map(|i| i as u8).collect();
+    let len = data.len() as u64;
+
+    for photo in [
+        InputFile::memory("cat.png", data.clone()),
+        InputFile::reader("cat.png", std::io::Cursor::new(data.clone())).with_len(len),
+    ] {
+        progress.lock().unwrap().clear();
+        bot.send_photo(SendPhotoPayload {
+            chat_id: ChatId::Id(42),
+            photo,
+            ..Default::default()
+        })
+        .await?;
+
+        let progress = progress.lock().unwrap().clone();
+        assert!(progress.len() > 1, "{:?}", progress);
+        assert!(progress
+            .iter()
+            .all(|(field, _, total)| field == "photo" && *total == Some(len)));
+        assert!(progress.windows(2).all(|w| w[0].1 < w[1].1));
+        assert_eq!(progress.last().map(|p| p.1), Some(len));
+    }
+    assert_eq!(
+        server.requests_for("sendPhoto")[1]
+            .file("photo")
+            .map(|f| f.data.clone()),
+        Some(data)
+    );
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::{sync::Mutex, time::Duration};
+
+use telegram_bot_api_rs::{
+    available_methods::payload::SendMessagePayload,
+    available_types::ChatId,
+    bot::CallApiResp,
+    error::Result,
+    middleware::{Middleware, Next},
+    rate_limit::{Quota, RateLimiter, RateLimits},
+    testing::FakeServer,
+    transport::ApiRequest,
+};
+
+/// Answers every call after the first one with the first response.
+#[derive(Debug, Default)]
+struct ReplayMiddleware {
+    response: Mutex<Option<CallApiResp>>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for ReplayMiddleware {
+    async fn handle(&self, request: ApiRequest, next: Next<'_>) -> Result<CallApiResp> {
+        if let Some(response) = self.response.lock().unwrap().clone() {
+            return Ok(response);
+        }
+        let response = next.run(request).await?;
+        *self.response.lock().unwrap() = Some(response.clone());
+        Ok(response)
+    }
+}
+
+#[tokio::test]
+async fn short_circuited_calls_skip_the_rate_limiter() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let quota = Quota::new(1, Duration::from_secs(60));
+    let bot = server
+        .bot()
+        .with_rate_limiter(RateLimiter::new(RateLimits {
+            per_private_chat: quota,
+            per_group: quota,
+            global: quota,
+        }))
+        .with_middleware(ReplayMiddleware::default());
+    let payload = SendMessagePayload {
+        chat_id: ChatId::Id(42),
+        text: "hi".to_string(),
+        ..Default::default()
+    };
+
+    for _ in 0..3 {
+        let message =
+            tokio::time::timeout(Duration::from_secs(5), bot.send_message(&payload)).await??;
+        assert_eq!(message.chat.id, 42);
+    }
+    assert_eq!(server.requests_for("sendMessage").len(), 1);
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use telegram_bot_api_rs::{
+    available_methods::payload::SendPhotoPayload,
+    available_types::{ChatId, InputFile},
+    testing::FakeServer,
+    upload_cache::{FileUploadCache, MemoryUploadCache},
+};
+
+fn photo() -> SendPhotoPayload {
+    SendPhotoPayload {
+        chat_id: ChatId::Id(42),
+        photo: InputFile::memory("cat.png", b"not really a png".to_vec()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn reuses_file_id_and_reuploads_when_it_is_stale() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot().with_upload_cache(MemoryUploadCache::new());
+
+    bot.send_photo(photo()).await?;
+    bot.send_photo(photo()).await?;
+    let requests = server.requests_for("sendPhoto");
+    assert!(requests[0].file("photo").is_some());
+    assert!(requests[1].file("photo").is_none());
+    let file_id = requests[1].param("photo").unwrap();
+
+    server.clear_requests();
+    server.enqueue_error(
+        "sendPhoto",
+        400,
+        "Bad Request: wrong file identifier/HTTP URL specified",
+    );
+    bot.send_photo(photo()).await?;
+    let requests = server.requests_for("sendPhoto");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].param("photo"), Some(file_id));
+    assert!(requests[1].file("photo").is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn keeps_file_id_on_unrelated_errors() -> anyhow::Result<()> {
+    let server = FakeServer::start().await?;
+    let bot = server.bot().with_upload_cache(MemoryUploadCache::new());
+    bot.send_photo(photo()).await?;
+
+    server.clear_requests();
+    server.enqueue_error("sendPhoto", 400, "Bad Request: chat not found");
+    let error = bot.send_photo(photo()).await.unwrap_err();
+    assert_eq!(error.error_code(), Some(400));
+    // Failed once, without uploading the file again.
+    assert_eq!(server.requests_for("sendPhoto").len(), 1);
+
+    bot.send_photo(photo()).await?;
+    assert!(server.requests_for("sendPhoto")[1].file("photo").is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_cache_survives_a_restart() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("upload_cache.json");
+    let server = FakeServer::start().await?;
+
+    let bot = server
+        .bot()
+        .with_upload_cache(FileUploadCache::open(&path).await?);
+    bot.send_photo(photo()).await?;
+    let uploaded = server.requests_for("sendPhoto")[0].file("photo").is_some();
+    assert!(uploaded);
+
+    let bot = server
+        .bot()
+        .with_upload_cache(FileUploadCache::open(&path).await?);
+    bot.send_photo(photo()).await?;
+    let requests = server.requests_for("sendPhoto");
+    assert!(requests[1].file("photo").is_none());
+    assert!(requests[1].param("photo").is_some());
+    assert!(!dir.path().join("upload_cache.tmp").exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_cache_evicts_stale_file_id() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("upload_cache.json");
+    let server = FakeServer::start().await?;
+    let bot = server
+        .bot()
+        .with_upload_cache(FileUploadCache::open(&path).await?);
+    bot.send_photo(photo()).await?;
+    bot.send_photo(photo()).await?;
+    let stale = server.requests_for("sendPhoto")[1].param("photo").unwrap();
+
+    server.clear_requests();
+    server.enqueue_error("sendPhoto", 400, "Bad Request: FILE_REFERENCE_EXPIRED");
+    bot.send_photo(photo()).await?;
+    let requests = server.requests_for("sendPhoto");
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].file("photo").is_some());
+
+    // The new file_id replaced the stale one on disk.
+    server.clear_requests();
+    let bot = server
+        .bot()
+        .with_upload_cache(FileUploadCache::open(&path).await?);
+    bot.send_photo(photo()).await?;
+    let file_id = server.requests_for("sendPhoto")[0].param("photo").unwrap();
+    assert_ne!(file_id, stale);
+    Ok(())
+}
+
+#[tokio::test]
+async fn path_and_memory_files_share_the_cache() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("cat.png");
+    // Larger than one read chunk, so hashing goes through several of them.
+    let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+    std::fs::write(&path, &data)?;
+    let server = FakeServer::start().await?;
+    let bot = server.bot().with_upload_cache(MemoryUploadCache::new());
+
+    bot.send_photo(SendPhotoPayload {
+        chat_id: ChatId::Id(42),
+        photo: InputFile::path(&path),
+        ..Default::default()
+    })
+    .await?;
+    bot.send_photo(SendPhotoPayload {
+        chat_id: ChatId::Id(42),
+        photo: InputFile::memory("cat.png", data),
+        ..Default::default()
+    })
+    .await?;
+    let requests = server.requests_for("sendPhoto");
+    assert_eq!(
+        requests[0].file("photo").map(|f| f.data.len()),
+        Some(200_000)
+    );
+    assert!(requests[1].file("photo").is_none());
+    Ok(())
+}
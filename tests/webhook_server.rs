@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use serde_json::json;
+use telegram_bot_api_rs::{
+    bot::Bot,
+    getting_updates::{
+        types::Update,
+        webhook_server::{WebhookConfig, WebhookHandle},
+        UpdateDelivery,
+    },
+};
+use tokio::sync::mpsc;
+
+const SECRET_TOKEN_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+async fn serve() -> anyhow::Result<(WebhookHandle, mpsc::Receiver<Update>, String)> {
+    let (sender, receiver) = mpsc::channel(8);
+    let server = Bot::new("1:a")
+        .serve_webhook(
+            "127.0.0.1:0",
+            WebhookConfig {
+                path: "/telegram".to_string(),
+                secret_token: Some("s3cret".to_string()),
+                delivery: UpdateDelivery::Channel(sender),
+                tls: None,
+            },
+        )
+        .await?;
+    let url = format!("http://{}/telegram", server.local_addr());
+    Ok((server, receiver, url))
+}
+
+fn update(update_id: i64) -> serde_json::Value {
+    json!({
+        "update_id": update_id,
+        "message": {
+            "message_id": 1,
+            "date": 1,
+            "chat": { "id": 42, "type": "private" },
+            "text": "hi",
+        }
+    })
+}
+
+#[tokio::test]
+async fn rejects_missing_or_wrong_secret_token() -> anyhow::Result<()> {
+    let (server, mut updates, url) = serve().await?;
+    let client = reqwest::Client::new();
+
+    let missing = client.post(&url).json(&update(1)).send().await?;
+    assert_eq!(missing.status(), 401);
+    let wrong = client
+        .post(&url)
+        .header(SECRET_TOKEN_HEADER, "s3cre7")
+        .json(&update(2))
+        .send()
+        .await?;
+    assert_eq!(wrong.status(), 401);
+
+    assert!(updates.try_recv().is_err());
+    server.stop().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn delivers_update_with_secret_token() -> anyhow::Result<()> {
+    let (server, mut updates, url) = serve().await?;
+
+    let resp = reqwest::Client::new()
+        .post(&url)
+        .header(SECRET_TOKEN_HEADER, "s3cret")
+        .json(&update(7))
+        .send()
+        .await?;
+    assert_eq!(resp.status(), 200);
+    let update = tokio::time::timeout(Duration::from_secs(5), updates.recv()).await?;
+    assert_eq!(update.map(|u| u.update_id), Some(7));
+
+    server.stop().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn malformed_body_is_rejected_without_stopping_the_server() -> anyhow::Result<()> {
+    let (server, mut updates, url) = serve().await?;
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .post(&url)
+        .header(SECRET_TOKEN_HEADER, "s3cret")
+        .body("{not json")
+        .send()
+        .await?;
+    assert_eq!(resp.status(), 400);
+    assert!(!server.is_finished());
+
+    let resp = client
+        .post(&url)
+        .header(SECRET_TOKEN_HEADER, "s3cret")
+        .json(&update(8))
+        .send()
+        .await?;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(updates.recv().await.map(|u| u.update_id), Some(8));
+
+    server.stop().await;
+    Ok(())
+}